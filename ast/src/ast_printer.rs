@@ -1,6 +1,7 @@
 use crate::expr::*;
 use lox_syntax::token::{Object, Token};
 
+#[derive(Default)]
 pub struct AstPrinter;
 
 impl AstPrinter {
@@ -17,7 +18,7 @@ impl AstPrinter {
         for &expr in exprs {
             s = format!("{s} {}", expr.accept(self));
         }
-        s.push_str(")");
+        s.push(')');
         s
     }
 }
@@ -31,6 +32,11 @@ impl Visitor<String> for AstPrinter {
         self.parenthesize("group", &[expression])
     }
 
+    fn visit_interpolation_expr(&mut self, parts: &[Expr]) -> String {
+        let parts: Vec<&Expr> = parts.iter().collect();
+        self.parenthesize("str", &parts)
+    }
+
     fn visit_literal_expr(&mut self, value: &Object) -> String {
         match value {
             Object::Nil => "nil".to_string(),
@@ -42,36 +48,90 @@ impl Visitor<String> for AstPrinter {
         self.parenthesize(operator.lexeme.as_str(), &[right])
     }
 
-    fn visit_assign_expr(&mut self, name: &Token, value: &Expr) -> String {
-        todo!()
+    fn visit_assign_expr(&mut self, name: &Token, value: &Expr, _id: ExprId) -> String {
+        self.parenthesize(&format!("= {}", name.lexeme), &[value])
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> String {
+        let mut exprs = vec![callee];
+        exprs.extend(arguments);
+        self.parenthesize("call", &exprs)
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> String {
+        self.parenthesize("?:", &[condition, then_branch, else_branch])
     }
 
-    fn visit_call_expr(&mut self, callee: &Expr, paren: &Token, arguments: &[Expr]) -> String {
-        todo!()
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> String {
+        self.parenthesize(operator.lexeme.as_str(), &[target, value])
     }
 
     fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> String {
-        todo!()
+        self.parenthesize(&format!(". {}", name.lexeme), &[object])
+    }
+
+    fn visit_increment_expr(&mut self, target: &Expr, operator: &Token, prefix: bool) -> String {
+        if prefix {
+            self.parenthesize(operator.lexeme.as_str(), &[target])
+        } else {
+            format!("({} {})", target.accept(self), operator.lexeme)
+        }
+    }
+
+    fn visit_index_expr(&mut self, object: &Expr, _bracket: &Token, index: &Expr) -> String {
+        self.parenthesize("[]", &[object, index])
+    }
+
+    fn visit_list_expr(&mut self, elements: &[Expr]) -> String {
+        let elements: Vec<&Expr> = elements.iter().collect();
+        self.parenthesize("list", &elements)
     }
 
     fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> String {
-        todo!()
+        self.parenthesize(operator.lexeme.as_str(), &[left, right])
+    }
+
+    fn visit_map_expr(&mut self, _brace: &Token, entries: &[(Expr, Expr)]) -> String {
+        let entries: Vec<&Expr> = entries
+            .iter()
+            .flat_map(|(key, value)| [key, value])
+            .collect();
+        self.parenthesize("map", &entries)
     }
 
     fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> String {
-        todo!()
+        self.parenthesize(&format!("= .{}", name.lexeme), &[object, value])
     }
 
-    fn visit_super_expr(&mut self, keyword: &Token, method: &Token) -> String {
-        todo!()
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> String {
+        self.parenthesize("[]=", &[object, index, value])
     }
 
-    fn visit_this_expr(&mut self, keyword: &Token) -> String {
-        todo!();
+    fn visit_super_expr(&mut self, keyword: &Token, method: &Token, _id: ExprId) -> String {
+        format!("{}.{}", keyword.lexeme, method.lexeme)
     }
 
-    fn visit_variable_expr(&mut self, keyword: &Token) -> String {
-        todo!()
+    fn visit_this_expr(&mut self, keyword: &Token, _id: ExprId) -> String {
+        keyword.lexeme.clone()
     }
-}
 
+    fn visit_variable_expr(&mut self, name: &Token, _id: ExprId) -> String {
+        name.lexeme.clone()
+    }
+}
@@ -1,19 +1,46 @@
 use lox_syntax::token::{Object, Token};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Identifies a variable reference so the resolver can record its scope depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExprId(usize);
+
+impl ExprId {
+    pub fn unique() -> ExprId {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        ExprId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum Expr {
     Binary(Box<Expr>, Token, Box<Expr>),
     Grouping(Box<Expr>),
+    /// `"a ${b} c"`: string segments and embedded expressions, in order.
+    Interpolation(Vec<Expr>),
     Literal(Object),
     Unary(Token, Box<Expr>),
-    Assign(Token, Box<Expr>),
+    Assign(Token, Box<Expr>, ExprId),
     Call(Box<Expr>, Token, Vec<Expr>),
+    /// `condition ? then_branch : else_branch`.
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// `target op= value` where the target is a `Variable`, `Get` or `Index`.
+    CompoundAssign(Box<Expr>, Token, Box<Expr>),
     Get(Box<Expr>, Token),
+    /// `++target` or `target--`; the flag is `true` for the prefix form.
+    Increment(Box<Expr>, Token, bool),
+    /// `object[index]`; the token is the closing bracket, used for error lines.
+    Index(Box<Expr>, Token, Box<Expr>),
+    List(Vec<Expr>),
     Logical(Box<Expr>, Token, Box<Expr>),
+    /// `{key: value, ...}`; the token is the opening brace.
+    Map(Token, Vec<(Expr, Expr)>),
     Set(Box<Expr>, Token, Box<Expr>),
-    Super(Token, Token),
-    This(Token),
-    Variable(Token),
+    /// `object[index] = value`.
+    SetIndex(Box<Expr>, Token, Box<Expr>, Box<Expr>),
+    Super(Token, Token, ExprId),
+    This(Token, ExprId),
+    Variable(Token, ExprId),
 }
 
 impl Expr {
@@ -21,20 +48,36 @@ impl Expr {
         match self {
             Expr::Binary(left, operator, right) => visitor.visit_binary_expr(left, operator, right),
             Expr::Grouping(expression) => visitor.visit_grouping_expr(expression),
+            Expr::Interpolation(parts) => visitor.visit_interpolation_expr(parts),
             Expr::Literal(value) => visitor.visit_literal_expr(value),
             Expr::Unary(operator, right) => visitor.visit_unary_expr(operator, right),
-            Expr::Assign(name, value) => visitor.visit_assign_expr(name, value),
+            Expr::Assign(name, value, id) => visitor.visit_assign_expr(name, value, *id),
             Expr::Call(callee, paren, arguments) => {
                 visitor.visit_call_expr(callee, paren, arguments)
             }
+            Expr::Conditional(condition, then_branch, else_branch) => {
+                visitor.visit_conditional_expr(condition, then_branch, else_branch)
+            }
+            Expr::CompoundAssign(target, operator, value) => {
+                visitor.visit_compound_assign_expr(target, operator, value)
+            }
             Expr::Get(object, name) => visitor.visit_get_expr(object, name),
+            Expr::Increment(target, operator, prefix) => {
+                visitor.visit_increment_expr(target, operator, *prefix)
+            }
+            Expr::Index(object, bracket, index) => visitor.visit_index_expr(object, bracket, index),
+            Expr::List(elements) => visitor.visit_list_expr(elements),
             Expr::Logical(left, operator, right) => {
                 visitor.visit_logical_expr(left, operator, right)
             }
+            Expr::Map(brace, entries) => visitor.visit_map_expr(brace, entries),
             Expr::Set(object, name, value) => visitor.visit_set_expr(object, name, value),
-            Expr::Super(keyword, method) => visitor.visit_super_expr(keyword, method),
-            Expr::This(keyword) => visitor.visit_this_expr(keyword),
-            Expr::Variable(name) => visitor.visit_variable_expr(name),
+            Expr::SetIndex(object, bracket, index, value) => {
+                visitor.visit_set_index_expr(object, bracket, index, value)
+            }
+            Expr::Super(keyword, method, id) => visitor.visit_super_expr(keyword, method, *id),
+            Expr::This(keyword, id) => visitor.visit_this_expr(keyword, *id),
+            Expr::Variable(name, id) => visitor.visit_variable_expr(name, *id),
         }
     }
 }
@@ -42,15 +85,33 @@ impl Expr {
 pub trait Visitor<T> {
     fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> T;
     fn visit_grouping_expr(&mut self, expression: &Expr) -> T;
+    fn visit_interpolation_expr(&mut self, parts: &[Expr]) -> T;
     fn visit_literal_expr(&mut self, value: &Object) -> T;
     fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> T;
-    fn visit_assign_expr(&mut self, name: &Token, value: &Expr) -> T;
+    fn visit_assign_expr(&mut self, name: &Token, value: &Expr, id: ExprId) -> T;
     fn visit_call_expr(&mut self, callee: &Expr, paren: &Token, arguments: &[Expr]) -> T;
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> T;
+    fn visit_compound_assign_expr(&mut self, target: &Expr, operator: &Token, value: &Expr) -> T;
     fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> T;
+    fn visit_increment_expr(&mut self, target: &Expr, operator: &Token, prefix: bool) -> T;
+    fn visit_index_expr(&mut self, object: &Expr, bracket: &Token, index: &Expr) -> T;
+    fn visit_list_expr(&mut self, elements: &[Expr]) -> T;
     fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> T;
+    fn visit_map_expr(&mut self, brace: &Token, entries: &[(Expr, Expr)]) -> T;
     fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> T;
-    fn visit_super_expr(&mut self, keyword: &Token, method: &Token) -> T;
-    fn visit_this_expr(&mut self, keyword: &Token) -> T;
-    fn visit_variable_expr(&mut self, name: &Token) -> T;
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> T;
+    fn visit_super_expr(&mut self, keyword: &Token, method: &Token, id: ExprId) -> T;
+    fn visit_this_expr(&mut self, keyword: &Token, id: ExprId) -> T;
+    fn visit_variable_expr(&mut self, name: &Token, id: ExprId) -> T;
 }
-
@@ -1,43 +1,66 @@
 use crate::expr::Expr;
 use lox_syntax::token::Token;
+use std::rc::Rc;
 
+#[derive(Debug)]
 pub enum Stmt {
     Block(Vec<Stmt>),
-    Class,
+    Class(Token, Option<Box<Expr>>, Vec<Rc<FunctionDecl>>),
     Expression(Box<Expr>),
-    Function,
-    If,
+    Function(Rc<FunctionDecl>),
+    If(Box<Expr>, Box<Stmt>, Option<Box<Stmt>>),
     Print(Box<Expr>),
-    Return,
+    Return(Token, Option<Box<Expr>>),
     Variable(Token, Option<Box<Expr>>),
-    While,
+    While(Box<Expr>, Box<Stmt>),
 }
 
 impl Stmt {
     pub fn accept<T>(&self, visitor: &mut dyn Visitor<T>) -> T {
         match self {
             Stmt::Block(statements) => visitor.visit_block_stmt(statements),
-            Stmt::Class => visitor.visit_class_stmt(),
+            Stmt::Class(name, superclass, methods) => {
+                visitor.visit_class_stmt(name, superclass, methods)
+            }
             Stmt::Expression(expression) => visitor.visit_expression_stmt(expression),
-            Stmt::Function => visitor.visit_function_stmt(),
-            Stmt::If => visitor.visit_if_stmt(),
+            Stmt::Function(declaration) => visitor.visit_function_stmt(declaration),
+            Stmt::If(condition, then_branch, else_branch) => {
+                visitor.visit_if_stmt(condition, then_branch, else_branch)
+            }
             Stmt::Print(value) => visitor.visit_print_stmt(value),
-            Stmt::Return => visitor.visit_return_stmt(),
+            Stmt::Return(keyword, value) => visitor.visit_return_stmt(keyword, value),
             Stmt::Variable(name, initializer) => visitor.visit_var_stmt(name, initializer),
-            Stmt::While => visitor.visit_while_stmt(),
+            Stmt::While(condition, body) => visitor.visit_while_stmt(condition, body),
         }
     }
 }
 
+/// Shared so runtime function values can hold on to their declaration.
+#[derive(Debug)]
+pub struct FunctionDecl {
+    pub name: Token,
+    pub params: Vec<Token>,
+    pub body: Vec<Stmt>,
+}
+
 pub trait Visitor<T> {
     fn visit_block_stmt(&mut self, statements: &[Stmt]) -> T;
-    fn visit_class_stmt(&mut self) -> T;
+    fn visit_class_stmt(
+        &mut self,
+        name: &Token,
+        superclass: &Option<Box<Expr>>,
+        methods: &[Rc<FunctionDecl>],
+    ) -> T;
     fn visit_expression_stmt(&mut self, expression: &Expr) -> T;
-    fn visit_function_stmt(&mut self) -> T;
-    fn visit_if_stmt(&mut self) -> T;
+    fn visit_function_stmt(&mut self, declaration: &Rc<FunctionDecl>) -> T;
+    fn visit_if_stmt(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: &Option<Box<Stmt>>,
+    ) -> T;
     fn visit_print_stmt(&mut self, value: &Expr) -> T;
-    fn visit_return_stmt(&mut self) -> T;
+    fn visit_return_stmt(&mut self, keyword: &Token, value: &Option<Box<Expr>>) -> T;
     fn visit_var_stmt(&mut self, name: &Token, initializer: &Option<Box<Expr>>) -> T;
-    fn visit_while_stmt(&mut self) -> T;
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> T;
 }
-
@@ -0,0 +1,100 @@
+use crate::class::LoxInstance;
+use crate::environment::Environment;
+use crate::interpreter::{Flow, Interpreter};
+use crate::value::Value;
+use ast::stmt::FunctionDecl;
+use result::result::LoxResult;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+pub trait LoxCallable {
+    fn arity(&self) -> usize;
+    /// `line` is the line of the call, for errors raised by the callee itself.
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        line: usize,
+    ) -> Result<Value, LoxResult>;
+}
+
+pub struct LoxFunction {
+    declaration: Rc<FunctionDecl>,
+    closure: Rc<RefCell<Environment>>,
+    is_initializer: bool,
+}
+
+impl LoxFunction {
+    pub fn new(
+        declaration: Rc<FunctionDecl>,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> LoxFunction {
+        LoxFunction {
+            declaration,
+            closure,
+            is_initializer,
+        }
+    }
+
+    /// Returns a copy of this method whose closure defines `this` as `instance`.
+    pub fn bind(&self, instance: Rc<RefCell<LoxInstance>>) -> LoxFunction {
+        let mut environment = Environment::new_with_enclosing(self.closure.clone());
+        environment.define("this", Value::Instance(instance));
+        LoxFunction::new(
+            self.declaration.clone(),
+            Rc::new(RefCell::new(environment)),
+            self.is_initializer,
+        )
+    }
+
+    fn bound_this(&self) -> Value {
+        self.closure
+            .borrow()
+            .lookup_at(0, "this")
+            .expect("Initializer is always bound to an instance")
+    }
+}
+
+impl LoxCallable for LoxFunction {
+    fn arity(&self) -> usize {
+        self.declaration.params.len()
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        _line: usize,
+    ) -> Result<Value, LoxResult> {
+        let mut environment = Environment::new_with_enclosing(self.closure.clone());
+        for (param, argument) in self.declaration.params.iter().zip(arguments) {
+            environment.define(&param.lexeme, argument);
+        }
+        let flow = interpreter.execute_block(&self.declaration.body, environment)?;
+        // An initializer always hands back the instance, even on a bare `return;`.
+        if self.is_initializer {
+            return Ok(self.bound_this());
+        }
+        match flow {
+            Flow::Return(value) => Ok(value),
+            Flow::Next => Ok(Value::Nil),
+        }
+    }
+}
+
+impl fmt::Display for LoxFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<fn {}>", self.declaration.name.lexeme)
+    }
+}
+
+// The closure usually contains the function itself, so only show the name.
+impl fmt::Debug for LoxFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoxFunction")
+            .field("name", &self.declaration.name.lexeme)
+            .finish()
+    }
+}
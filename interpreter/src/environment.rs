@@ -1,4 +1,5 @@
-use lox_syntax::token::{Object, Token};
+use crate::value::Value;
+use lox_syntax::token::Token;
 use result::result::LoxResult;
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
@@ -8,7 +9,13 @@ use std::rc::Rc;
 #[derive(Debug)]
 pub struct Environment {
     enclosing: Option<Rc<RefCell<Environment>>>,
-    values: HashMap<String, Object>,
+    values: HashMap<String, Value>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment::new()
+    }
 }
 
 impl Environment {
@@ -19,7 +26,7 @@ impl Environment {
         }
     }
 
-    pub fn get(&mut self, name: &Token) -> Result<Object, LoxResult> {
+    pub fn get(&mut self, name: &Token) -> Result<Value, LoxResult> {
         let name_str = name.lexeme.as_str();
         if let Entry::Occupied(object) = self.values.entry(name.lexeme.clone()) {
             Ok(object.get().clone())
@@ -33,11 +40,11 @@ impl Environment {
         }
     }
 
-    pub fn define(&mut self, name: &str, value: Object) {
+    pub fn define(&mut self, name: &str, value: Value) {
         self.values.insert(name.to_owned(), value);
     }
 
-    pub fn assign(&mut self, name: &Token, value: Object) -> Result<(), LoxResult> {
+    pub fn assign(&mut self, name: &Token, value: Value) -> Result<(), LoxResult> {
         if let Entry::Occupied(mut object) = self.values.entry(name.lexeme.clone()) {
             object.insert(value);
             Ok(())
@@ -51,6 +58,40 @@ impl Environment {
         }
     }
 
+    pub fn get_at(&self, distance: usize, name: &Token) -> Result<Value, LoxResult> {
+        self.lookup_at(distance, &name.lexeme)
+            .ok_or_else(|| Environment::undefined(name))
+    }
+
+    pub fn lookup_at(&self, distance: usize, name: &str) -> Option<Value> {
+        if distance == 0 {
+            return self.values.get(name).cloned();
+        }
+        self.enclosing
+            .as_ref()
+            .and_then(|enclosing| enclosing.borrow().lookup_at(distance - 1, name))
+    }
+
+    pub fn assign_at(
+        &mut self,
+        distance: usize,
+        name: &Token,
+        value: Value,
+    ) -> Result<(), LoxResult> {
+        if distance == 0 {
+            self.values.insert(name.lexeme.clone(), value);
+            return Ok(());
+        }
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign_at(distance - 1, name, value),
+            None => Err(Environment::undefined(name)),
+        }
+    }
+
+    fn undefined(name: &Token) -> LoxResult {
+        LoxResult::run_time_error(name.line, &format!("Undefined variable '{}'.", name.lexeme))
+    }
+
     pub fn new_with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Environment {
         Environment {
             enclosing: Some(enclosing),
@@ -58,4 +99,3 @@ impl Environment {
         }
     }
 }
-
@@ -1,80 +1,207 @@
+use crate::callable::{LoxCallable, LoxFunction};
+use crate::class::{LoxClass, LoxInstance};
 use crate::environment::Environment;
+use crate::map::LoxMap;
+use crate::native::{self, NativeFunction};
+use crate::value::Value;
 use ast::expr;
-use ast::expr::Expr;
+use ast::expr::{Expr, ExprId};
 use ast::stmt;
-use ast::stmt::Stmt;
+use ast::stmt::{FunctionDecl, Stmt};
 use lox_syntax::token::{Object, Token, TokenType};
 use result::result::LoxResult;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
+/// How many calls may be active at once before a runaway recursion is
+/// reported as a runtime error rather than overflowing the native stack.
+const MAX_CALL_DEPTH: usize = 255;
+
+/// How control leaves a statement: falling through to the next one, or
+/// unwinding to the enclosing call with a `return` value.
+#[derive(Debug)]
+pub enum Flow {
+    Next,
+    Return(Value),
+}
+
 #[derive(Debug)]
 pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
     environment: Rc<RefCell<Environment>>,
+    locals: HashMap<ExprId, usize>,
+    call_depth: usize,
 }
 
 impl Interpreter {
     pub fn new() -> Interpreter {
-        Interpreter {
-            environment: Rc::new(RefCell::new(Environment::new())),
+        let globals = Rc::new(RefCell::new(Environment::new()));
+        let mut interpreter = Interpreter {
+            environment: globals.clone(),
+            globals,
+            locals: HashMap::new(),
+            call_depth: 0,
+        };
+        for native in native::core_natives() {
+            interpreter.define_native(native);
         }
+        interpreter
     }
 
-    pub fn evaluate(&mut self, expression: &Expr) -> Result<Object, LoxResult> {
+    /// Makes a Rust closure callable from scripts under its native name.
+    pub fn define_native(&mut self, native: NativeFunction) {
+        let name = native.name().to_owned();
+        self.globals
+            .borrow_mut()
+            .define(&name, Value::Native(Rc::new(native)));
+    }
+
+    pub fn evaluate(&mut self, expression: &Expr) -> Result<Value, LoxResult> {
         expression.accept(self)
     }
 
-    pub fn interpret(&mut self, statements: &Vec<Stmt>) -> Result<(), LoxResult> {
+    pub fn interpret(&mut self, statements: &[Stmt]) -> Result<(), LoxResult> {
         for statement in statements.iter() {
-            self.execute(statement)?
+            self.execute(statement)?;
         }
         Ok(())
     }
 
-    fn execute(&mut self, statement: &Stmt) -> Result<(), LoxResult> {
+    /// Records how many scopes away from its use a local variable was declared.
+    pub(crate) fn resolve(&mut self, id: ExprId, depth: usize) {
+        self.locals.insert(id, depth);
+    }
+
+    fn look_up_variable(&self, name: &Token, id: ExprId) -> Result<Value, LoxResult> {
+        match self.locals.get(&id) {
+            Some(distance) => self.environment.borrow().get_at(*distance, name),
+            None => self.globals.borrow_mut().get(name),
+        }
+    }
+
+    fn assign_variable(&mut self, name: &Token, id: ExprId, value: Value) -> Result<(), LoxResult> {
+        match self.locals.get(&id) {
+            Some(distance) => self
+                .environment
+                .borrow_mut()
+                .assign_at(*distance, name, value),
+            None => self.globals.borrow_mut().assign(name, value),
+        }
+    }
+
+    /// Reads an assignable target, stores `apply(current)` back into it and
+    /// returns both the previous and the stored value. Each part of the
+    /// target is evaluated once, before `apply` runs.
+    fn update(
+        &mut self,
+        target: &Expr,
+        line: usize,
+        apply: impl FnOnce(&mut Interpreter, Value) -> Result<Value, LoxResult>,
+    ) -> Result<(Value, Value), LoxResult> {
+        match target {
+            Expr::Variable(name, id) => {
+                let current = self.look_up_variable(name, *id)?;
+                let result = apply(self, current.clone())?;
+                self.assign_variable(name, *id, result.clone())?;
+                Ok((current, result))
+            }
+            Expr::Get(object, name) => {
+                let Value::Instance(instance) = self.evaluate(object)? else {
+                    return Err(LoxResult::run_time_error(
+                        name.line,
+                        "Only instances have fields.",
+                    ));
+                };
+                let current = LoxInstance::get(&instance, name)?;
+                let result = apply(self, current.clone())?;
+                instance.borrow_mut().set(name, result.clone());
+                Ok((current, result))
+            }
+            Expr::Index(object, bracket, index) => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                let current = object.get_index(&index, bracket.line)?;
+                let result = apply(self, current.clone())?;
+                object.set_index(&index, result.clone(), bracket.line)?;
+                Ok((current, result))
+            }
+            _ => Err(LoxResult::run_time_error(
+                line,
+                "Invalid assignment target.",
+            )),
+        }
+    }
+
+    /// Applies the arithmetic behind a compound assignment such as `+=`.
+    fn compound(operator: &Token, left: Value, right: Value) -> Result<Value, LoxResult> {
+        match operator.ttype {
+            TokenType::PlusEqual => Value::add(left, right, operator.line),
+            TokenType::MinusEqual => Value::subtract(left, right, operator.line),
+            TokenType::StarEqual => Value::multiply(left, right, operator.line),
+            TokenType::SlashEqual => Value::divide(left, right, operator.line),
+            _ => Err(LoxResult::run_time_error(
+                operator.line,
+                "Incorrect operator for compound assignment",
+            )),
+        }
+    }
+
+    fn execute(&mut self, statement: &Stmt) -> Result<Flow, LoxResult> {
         statement.accept(self)
     }
 
-    fn execute_block(
+    pub(crate) fn execute_block(
         &mut self,
         statements: &[Stmt],
         environment: Environment,
-    ) -> Result<(), LoxResult> {
-        let previous = self.environment.clone();
-        self.environment = Rc::new(RefCell::new(environment));
-        //std::mem::swap(&mut self.environment, &mut env);
+    ) -> Result<Flow, LoxResult> {
+        let previous = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(environment)));
+        let result = self.execute_statements(statements);
+        self.environment = previous;
+        result
+    }
+
+    fn execute_statements(&mut self, statements: &[Stmt]) -> Result<Flow, LoxResult> {
         for stmt in statements {
-            self.execute(stmt)?
+            if let Flow::Return(value) = self.execute(stmt)? {
+                return Ok(Flow::Return(value));
+            }
         }
-        self.environment = previous.clone();
-        //std::mem::swap(&mut self.environment, &mut env);
-        Ok(())
+        Ok(Flow::Next)
     }
 }
 
-impl expr::Visitor<Result<Object, LoxResult>> for Interpreter {
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
+
+impl expr::Visitor<Result<Value, LoxResult>> for Interpreter {
     fn visit_binary_expr(
         &mut self,
         left: &Expr,
         operator: &Token,
         right: &Expr,
-    ) -> Result<Object, LoxResult> {
-        let left_object = self.evaluate(left)?;
-        let right_object = self.evaluate(right)?;
+    ) -> Result<Value, LoxResult> {
+        let left_value = self.evaluate(left)?;
+        let right_value = self.evaluate(right)?;
 
         match operator.ttype {
-            TokenType::Minus => Object::subtract(left_object, right_object, operator.line),
-            TokenType::Slash => Object::divide(left_object, right_object, operator.line),
-            TokenType::Star => Object::multiply(left_object, right_object, operator.line),
-            TokenType::Plus => Object::add(left_object, right_object, operator.line),
-            TokenType::Greater => Object::greater(left_object, right_object, operator.line),
-            TokenType::GreaterEqual => {
-                Object::greater_equal(left_object, right_object, operator.line)
-            }
-            TokenType::Less => Object::less(left_object, right_object, operator.line),
-            TokenType::LessEqual => Object::less_equal(left_object, right_object, operator.line),
-            TokenType::BangEqual => Object::bang_equal(left_object, right_object, operator.line),
-            TokenType::Equals => Object::equals(left_object, right_object, operator.line),
+            TokenType::Minus => Value::subtract(left_value, right_value, operator.line),
+            TokenType::Slash => Value::divide(left_value, right_value, operator.line),
+            TokenType::Star => Value::multiply(left_value, right_value, operator.line),
+            TokenType::Percent => Value::modulo(left_value, right_value, operator.line),
+            TokenType::StarStar => Value::power(left_value, right_value, operator.line),
+            TokenType::TildeSlash => Value::floor_divide(left_value, right_value, operator.line),
+            TokenType::Plus => Value::add(left_value, right_value, operator.line),
+            TokenType::Greater => Value::greater(left_value, right_value, operator.line),
+            TokenType::GreaterEqual => Value::greater_equal(left_value, right_value, operator.line),
+            TokenType::Less => Value::less(left_value, right_value, operator.line),
+            TokenType::LessEqual => Value::less_equal(left_value, right_value, operator.line),
+            TokenType::BangEqual => Ok(Value::Bool(left_value != right_value)),
+            TokenType::Equals => Ok(Value::Bool(left_value == right_value)),
             _ => Err(LoxResult::run_time_error(
                 operator.line,
                 "Incorrect operator for binary expression",
@@ -82,19 +209,27 @@ impl expr::Visitor<Result<Object, LoxResult>> for Interpreter {
         }
     }
 
-    fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<Object, LoxResult> {
+    fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<Value, LoxResult> {
         self.evaluate(expression)
     }
 
-    fn visit_literal_expr(&mut self, value: &Object) -> Result<Object, LoxResult> {
-        Ok(value.clone())
+    fn visit_interpolation_expr(&mut self, parts: &[Expr]) -> Result<Value, LoxResult> {
+        let mut string = String::new();
+        for part in parts {
+            string.push_str(&self.evaluate(part)?.stringify());
+        }
+        Ok(Value::Str(string))
+    }
+
+    fn visit_literal_expr(&mut self, value: &Object) -> Result<Value, LoxResult> {
+        Ok(Value::from(value.clone()))
     }
 
-    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<Object, LoxResult> {
-        let right_object = self.evaluate(right).unwrap();
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<Value, LoxResult> {
+        let right_value = self.evaluate(right)?;
         match operator.ttype {
-            TokenType::Minus => right_object.negate(operator.line),
-            TokenType::Bang => Ok(right_object.is_truthy()),
+            TokenType::Minus => right_value.negate(operator.line),
+            TokenType::Bang => Ok(Value::Bool(!right_value.is_truthy())),
             _ => Err(LoxResult::run_time_error(
                 operator.line,
                 "Incorrect operator for unary expression",
@@ -102,9 +237,14 @@ impl expr::Visitor<Result<Object, LoxResult>> for Interpreter {
         }
     }
 
-    fn visit_assign_expr(&mut self, name: &Token, value: &Expr) -> Result<Object, LoxResult> {
+    fn visit_assign_expr(
+        &mut self,
+        name: &Token,
+        value: &Expr,
+        id: ExprId,
+    ) -> Result<Value, LoxResult> {
         let value = self.evaluate(value)?;
-        self.environment.borrow_mut().assign(name, value.clone())?;
+        self.assign_variable(name, id, value.clone())?;
         Ok(value)
     }
 
@@ -113,12 +253,112 @@ impl expr::Visitor<Result<Object, LoxResult>> for Interpreter {
         callee: &Expr,
         paren: &Token,
         arguments: &[Expr],
-    ) -> Result<Object, LoxResult> {
-        todo!()
+    ) -> Result<Value, LoxResult> {
+        let callee = self.evaluate(callee)?;
+        let arguments = arguments
+            .iter()
+            .map(|argument| self.evaluate(argument))
+            .collect::<Result<Vec<Value>, LoxResult>>()?;
+
+        let callable: &dyn LoxCallable = match &callee {
+            Value::Function(function) => function.as_ref(),
+            Value::Class(class) => class,
+            Value::Native(native) => native.as_ref(),
+            _ => {
+                return Err(LoxResult::run_time_error(
+                    paren.line,
+                    "Can only call functions and classes.",
+                ))
+            }
+        };
+        if arguments.len() != callable.arity() {
+            return Err(LoxResult::run_time_error(
+                paren.line,
+                &format!(
+                    "Expected {} arguments but got {}.",
+                    callable.arity(),
+                    arguments.len()
+                ),
+            ));
+        }
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(LoxResult::run_time_error(paren.line, "Stack overflow."));
+        }
+        self.call_depth += 1;
+        let result = callable.call(self, arguments, paren.line);
+        self.call_depth -= 1;
+        result
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> Result<Value, LoxResult> {
+        if self.evaluate(condition)?.is_truthy() {
+            self.evaluate(then_branch)
+        } else {
+            self.evaluate(else_branch)
+        }
+    }
+
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> Result<Value, LoxResult> {
+        let (_, result) = self.update(target, operator.line, |interpreter, current| {
+            let value = interpreter.evaluate(value)?;
+            Interpreter::compound(operator, current, value)
+        })?;
+        Ok(result)
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<Value, LoxResult> {
+        match self.evaluate(object)? {
+            Value::Instance(instance) => LoxInstance::get(&instance, name),
+            _ => Err(LoxResult::run_time_error(
+                name.line,
+                "Only instances have properties.",
+            )),
+        }
     }
 
-    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<Object, LoxResult> {
-        todo!()
+    fn visit_increment_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        prefix: bool,
+    ) -> Result<Value, LoxResult> {
+        let (previous, result) = self.update(target, operator.line, |_, current| {
+            let one = Value::Num(1.0);
+            match operator.ttype {
+                TokenType::PlusPlus => Value::add(current, one, operator.line),
+                _ => Value::subtract(current, one, operator.line),
+            }
+        })?;
+        Ok(if prefix { result } else { previous })
+    }
+
+    fn visit_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+    ) -> Result<Value, LoxResult> {
+        let object = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+        object.get_index(&index, bracket.line)
+    }
+
+    fn visit_list_expr(&mut self, elements: &[Expr]) -> Result<Value, LoxResult> {
+        let elements = elements
+            .iter()
+            .map(|element| self.evaluate(element))
+            .collect::<Result<Vec<Value>, LoxResult>>()?;
+        Ok(Value::List(Rc::new(RefCell::new(elements))))
     }
 
     fn visit_logical_expr(
@@ -126,8 +366,34 @@ impl expr::Visitor<Result<Object, LoxResult>> for Interpreter {
         left: &Expr,
         operator: &Token,
         right: &Expr,
-    ) -> Result<Object, LoxResult> {
-        todo!()
+    ) -> Result<Value, LoxResult> {
+        let left_value = self.evaluate(left)?;
+        let left_truthy = left_value.is_truthy();
+
+        // Return the operand that decided the result rather than a boolean.
+        match operator.ttype {
+            TokenType::Or if left_truthy => Ok(left_value),
+            TokenType::And if !left_truthy => Ok(left_value),
+            TokenType::Or | TokenType::And => self.evaluate(right),
+            _ => Err(LoxResult::run_time_error(
+                operator.line,
+                "Incorrect operator for logical expression",
+            )),
+        }
+    }
+
+    fn visit_map_expr(
+        &mut self,
+        brace: &Token,
+        entries: &[(Expr, Expr)],
+    ) -> Result<Value, LoxResult> {
+        let mut map = LoxMap::new();
+        for (key, value) in entries {
+            let key = self.evaluate(key)?;
+            let value = self.evaluate(value)?;
+            map.set(key, value, brace.line)?;
+        }
+        Ok(Value::Map(Rc::new(RefCell::new(map))))
     }
 
     fn visit_set_expr(
@@ -135,74 +401,192 @@ impl expr::Visitor<Result<Object, LoxResult>> for Interpreter {
         object: &Expr,
         name: &Token,
         value: &Expr,
-    ) -> Result<Object, LoxResult> {
-        todo!()
+    ) -> Result<Value, LoxResult> {
+        let Value::Instance(instance) = self.evaluate(object)? else {
+            return Err(LoxResult::run_time_error(
+                name.line,
+                "Only instances have fields.",
+            ));
+        };
+        let value = self.evaluate(value)?;
+        instance.borrow_mut().set(name, value.clone());
+        Ok(value)
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<Value, LoxResult> {
+        let object = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+        let value = self.evaluate(value)?;
+        object.set_index(&index, value.clone(), bracket.line)?;
+        Ok(value)
     }
 
-    fn visit_super_expr(&mut self, keyword: &Token, method: &Token) -> Result<Object, LoxResult> {
-        todo!()
+    fn visit_super_expr(
+        &mut self,
+        keyword: &Token,
+        method: &Token,
+        id: ExprId,
+    ) -> Result<Value, LoxResult> {
+        // The resolver puts `super` one scope outside the method's `this`.
+        let (superclass, this) = match self.locals.get(&id) {
+            Some(&distance) if distance > 0 => {
+                let environment = self.environment.borrow();
+                (
+                    environment.get_at(distance, keyword)?,
+                    environment.lookup_at(distance - 1, "this"),
+                )
+            }
+            _ => (Value::Nil, None),
+        };
+        let (Value::Class(superclass), Some(Value::Instance(instance))) = (superclass, this) else {
+            return Err(LoxResult::run_time_error(
+                keyword.line,
+                "Can't use 'super' outside of a subclass method.",
+            ));
+        };
+        match superclass.find_method(&method.lexeme) {
+            Some(function) => Ok(Value::Function(Rc::new(function.bind(instance)))),
+            None => Err(LoxResult::run_time_error(
+                method.line,
+                &format!("Undefined property '{}'.", method.lexeme),
+            )),
+        }
     }
 
-    fn visit_this_expr(&mut self, keyword: &Token) -> Result<Object, LoxResult> {
-        todo!()
+    fn visit_this_expr(&mut self, keyword: &Token, id: ExprId) -> Result<Value, LoxResult> {
+        self.look_up_variable(keyword, id)
     }
 
-    fn visit_variable_expr(&mut self, name: &Token) -> Result<Object, LoxResult> {
-        self.environment.borrow_mut().get(name)
+    fn visit_variable_expr(&mut self, name: &Token, id: ExprId) -> Result<Value, LoxResult> {
+        self.look_up_variable(name, id)
     }
 }
 
-impl stmt::Visitor<Result<(), LoxResult>> for Interpreter {
-    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<(), LoxResult> {
+impl stmt::Visitor<Result<Flow, LoxResult>> for Interpreter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<Flow, LoxResult> {
         self.execute_block(
-            &statements,
+            statements,
             Environment::new_with_enclosing(self.environment.clone()),
         )
     }
 
-    fn visit_class_stmt(&mut self) -> Result<(), LoxResult> {
-        todo!()
+    fn visit_class_stmt(
+        &mut self,
+        name: &Token,
+        superclass: &Option<Box<Expr>>,
+        methods: &[Rc<FunctionDecl>],
+    ) -> Result<Flow, LoxResult> {
+        let superclass = match superclass {
+            Some(superclass) => match self.evaluate(superclass)? {
+                Value::Class(class) => Some(class),
+                _ => {
+                    return Err(LoxResult::run_time_error(
+                        name.line,
+                        "Superclass must be a class.",
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        // Methods of a subclass close over an extra scope that binds `super`.
+        let method_environment = match &superclass {
+            Some(superclass) => {
+                let mut environment = Environment::new_with_enclosing(self.environment.clone());
+                environment.define("super", Value::Class(superclass.clone()));
+                Rc::new(RefCell::new(environment))
+            }
+            None => self.environment.clone(),
+        };
+
+        let methods = methods
+            .iter()
+            .map(|method| {
+                let is_initializer = method.name.lexeme == "init";
+                let function =
+                    LoxFunction::new(method.clone(), method_environment.clone(), is_initializer);
+                (method.name.lexeme.clone(), Rc::new(function))
+            })
+            .collect();
+        let class = LoxClass::new(&name.lexeme, superclass, methods);
+        self.environment
+            .borrow_mut()
+            .define(&name.lexeme, Value::Class(Rc::new(class)));
+        Ok(Flow::Next)
     }
 
-    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<(), LoxResult> {
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<Flow, LoxResult> {
         self.evaluate(expression)?;
-        Ok(())
+        Ok(Flow::Next)
     }
 
-    fn visit_function_stmt(&mut self) -> Result<(), LoxResult> {
-        todo!()
+    fn visit_function_stmt(&mut self, declaration: &Rc<FunctionDecl>) -> Result<Flow, LoxResult> {
+        let function = LoxFunction::new(declaration.clone(), self.environment.clone(), false);
+        self.environment
+            .borrow_mut()
+            .define(&declaration.name.lexeme, Value::Function(Rc::new(function)));
+        Ok(Flow::Next)
     }
 
-    fn visit_if_stmt(&mut self) -> Result<(), LoxResult> {
-        todo!()
+    fn visit_if_stmt(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: &Option<Box<Stmt>>,
+    ) -> Result<Flow, LoxResult> {
+        if self.evaluate(condition)?.is_truthy() {
+            self.execute(then_branch)
+        } else if let Some(else_branch) = else_branch {
+            self.execute(else_branch)
+        } else {
+            Ok(Flow::Next)
+        }
     }
 
-    fn visit_print_stmt(&mut self, value: &Expr) -> Result<(), LoxResult> {
+    fn visit_print_stmt(&mut self, value: &Expr) -> Result<Flow, LoxResult> {
         let value = self.evaluate(value)?;
         println!("{}", value.stringify());
-        Ok(())
+        Ok(Flow::Next)
     }
 
-    fn visit_return_stmt(&mut self) -> Result<(), LoxResult> {
-        todo!()
+    fn visit_return_stmt(
+        &mut self,
+        _keyword: &Token,
+        value: &Option<Box<Expr>>,
+    ) -> Result<Flow, LoxResult> {
+        let value = match value {
+            Some(value) => self.evaluate(value)?,
+            None => Value::Nil,
+        };
+        Ok(Flow::Return(value))
     }
 
     fn visit_var_stmt(
         &mut self,
         name: &Token,
         initializer: &Option<Box<Expr>>,
-    ) -> Result<(), LoxResult> {
-        let mut value = Object::Nil;
+    ) -> Result<Flow, LoxResult> {
+        let mut value = Value::Nil;
         if let Some(init_value) = initializer {
             value = self.evaluate(init_value)?;
         }
         self.environment.borrow_mut().define(&name.lexeme, value);
 
-        Ok(())
+        Ok(Flow::Next)
     }
 
-    fn visit_while_stmt(&mut self) -> Result<(), LoxResult> {
-        todo!()
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<Flow, LoxResult> {
+        while self.evaluate(condition)?.is_truthy() {
+            if let Flow::Return(value) = self.execute(body)? {
+                return Ok(Flow::Return(value));
+            }
+        }
+        Ok(Flow::Next)
     }
 }
-
@@ -1,5 +1,11 @@
+pub mod callable;
+pub mod class;
 pub mod environment;
 pub mod interpreter;
+pub mod map;
+pub mod native;
+pub mod resolver;
+pub mod value;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
@@ -7,11 +13,949 @@ pub fn add(left: usize, right: usize) -> usize {
 
 #[cfg(test)]
 mod tests {
+    use super::interpreter::Interpreter;
+    use super::native::NativeFunction;
+    use super::resolver::Resolver;
+    use super::value::Value;
     use super::*;
+    use ast::stmt::Stmt;
+    use lox_syntax::scanner::Scanner;
+    use parser::parser::Parser;
+    use result::result::LoxResult;
+
+    fn parse(source: &str) -> Vec<Stmt> {
+        let tokens = Scanner::new(source).scan_tokens().unwrap();
+        Parser::new(tokens).parse().unwrap()
+    }
+
+    fn run(source: &str) -> Interpreter {
+        let statements = parse(source);
+        let mut interpreter = Interpreter::new();
+        Resolver::new(&mut interpreter)
+            .resolve(&statements)
+            .unwrap();
+        interpreter.interpret(&statements).unwrap();
+        interpreter
+    }
+
+    fn run_err(source: &str) -> LoxResult {
+        let statements = parse(source);
+        let mut interpreter = Interpreter::new();
+        match Resolver::new(&mut interpreter).resolve(&statements) {
+            Ok(()) => interpreter.interpret(&statements).unwrap_err(),
+            Err(error) => error,
+        }
+    }
+
+    fn evaluate(interpreter: &mut Interpreter, source: &str) -> Value {
+        match parse(&format!("{source};")).remove(0) {
+            Stmt::Expression(expr) => interpreter.evaluate(&expr).unwrap(),
+            _ => panic!("Expected expression statement"),
+        }
+    }
 
     #[test]
     fn it_works() {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn test_logical_operators_return_deciding_operand() {
+        let mut interpreter = run("");
+        assert_eq!(
+            evaluate(&mut interpreter, "nil or \"yes\"").stringify(),
+            "yes"
+        );
+        assert_eq!(
+            evaluate(&mut interpreter, "\"first\" or \"second\"").stringify(),
+            "first"
+        );
+        assert_eq!(
+            evaluate(&mut interpreter, "nil and \"never\"").stringify(),
+            "nil"
+        );
+        assert_eq!(
+            evaluate(&mut interpreter, "true and \"last\"").stringify(),
+            "last"
+        );
+    }
+
+    #[test]
+    fn test_logical_operators_short_circuit() {
+        let mut interpreter =
+            run("var a = \"unchanged\"; true or (a = \"changed\"); false and (a = \"changed\");");
+        assert_eq!(evaluate(&mut interpreter, "a").stringify(), "unchanged");
+    }
+
+    #[test]
+    fn test_function_call_binds_parameters() {
+        let mut interpreter =
+            run("var result; fun join(a, b) { result = a + b; } join(\"foo\", \"bar\");");
+        assert_eq!(evaluate(&mut interpreter, "result").stringify(), "foobar");
+    }
+
+    #[test]
+    fn test_function_value_stringifies_with_name() {
+        let mut interpreter = run("fun greet() {}");
+        assert_eq!(
+            evaluate(&mut interpreter, "greet").stringify(),
+            "<fn greet>"
+        );
+        assert_eq!(evaluate(&mut interpreter, "greet()").stringify(), "nil");
+    }
+
+    #[test]
+    fn test_call_with_wrong_arity_is_runtime_error() {
+        match run_err("fun f(a, b) {} f(1);") {
+            LoxResult::RunTimeError { message, .. } => {
+                assert_eq!(message, "Expected 2 arguments but got 1.")
+            }
+            _ => panic!("Expected runtime error"),
+        }
+    }
+
+    #[test]
+    fn test_calling_non_callable_is_runtime_error() {
+        assert!(matches!(
+            run_err("\"not a function\"();"),
+            LoxResult::RunTimeError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_unbounded_recursion_is_stack_overflow_error() {
+        match run_err("fun f() {\n  return f();\n}\nf();") {
+            LoxResult::RunTimeError { line, message } => {
+                assert_eq!(message, "Stack overflow.");
+                assert_eq!(line, 2);
+            }
+            _ => panic!("Expected runtime error"),
+        }
+        let mut interpreter = run("fun f() { return f(); }
+            fun depth(n) { if (n == 0) return 0; return depth(n - 1) + 1; }");
+        match parse("f();").remove(0) {
+            Stmt::Expression(expr) => assert!(interpreter.evaluate(&expr).is_err()),
+            _ => panic!("Expected expression statement"),
+        }
+        assert_eq!(evaluate(&mut interpreter, "depth(200)").stringify(), "200");
+    }
+
+    #[test]
+    fn test_return_unwinds_through_loops_and_blocks() {
+        let mut interpreter =
+            run("fun find() { var i = 0; while (true) { { if (i == 3) return i; } i = i + 1; } }");
+        assert_eq!(evaluate(&mut interpreter, "find()").stringify(), "3");
+    }
+
+    #[test]
+    fn test_bare_return_yields_nil() {
+        let mut interpreter = run("var after = false; fun f() { return; after = true; }");
+        assert_eq!(evaluate(&mut interpreter, "f()").stringify(), "nil");
+        assert_eq!(evaluate(&mut interpreter, "after").stringify(), "false");
+    }
+
+    #[test]
+    fn test_recursive_function() {
+        let mut interpreter =
+            run("fun fib(n) { if (n < 2) return n; return fib(n - 1) + fib(n - 2); }");
+        assert_eq!(evaluate(&mut interpreter, "fib(10)").stringify(), "55");
+    }
+
+    #[test]
+    fn test_closure_keeps_counter_state() {
+        let mut interpreter = run("fun makeCounter() {
+                var i = 0;
+                fun count() { i = i + 1; return i; }
+                return count;
+            }
+            var counter = makeCounter();
+            counter();
+            var other = makeCounter();");
+        assert_eq!(evaluate(&mut interpreter, "counter()").stringify(), "2");
+        assert_eq!(evaluate(&mut interpreter, "other()").stringify(), "1");
+    }
+
+    #[test]
+    fn test_closure_captures_parameters() {
+        let mut interpreter = run("fun adder(n) { fun add(x) { return x + n; } return add; }
+            var addTen = adder(10);");
+        assert_eq!(evaluate(&mut interpreter, "addTen(5)").stringify(), "15");
+    }
+
+    #[test]
+    fn test_closure_ignores_later_shadowing_declaration() {
+        let mut interpreter = run("var a = \"global\";
+            var first;
+            var second;
+            {
+                fun showA() { return a; }
+                first = showA();
+                var a = \"block\";
+                second = showA();
+            }");
+        assert_eq!(evaluate(&mut interpreter, "first").stringify(), "global");
+        assert_eq!(evaluate(&mut interpreter, "second").stringify(), "global");
+    }
+
+    #[test]
+    fn test_resolver_rejects_reading_local_in_own_initializer() {
+        assert!(matches!(
+            run_err("{ var a = a; }"),
+            LoxResult::ParseError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_resolver_rejects_duplicate_local_declaration() {
+        assert!(matches!(
+            run_err("fun f() { var a = 1; var a = 2; }"),
+            LoxResult::ParseError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_class_instances_hold_fields() {
+        let mut interpreter = run("class Bag {} var bag = Bag(); bag.item = \"apple\";");
+        assert_eq!(evaluate(&mut interpreter, "Bag").stringify(), "Bag");
+        assert_eq!(
+            evaluate(&mut interpreter, "bag").stringify(),
+            "Bag instance"
+        );
+        assert_eq!(evaluate(&mut interpreter, "bag.item").stringify(), "apple");
+    }
+
+    #[test]
+    fn test_methods_are_bound_to_this() {
+        let mut interpreter = run("class Person {
+                init(name) { this.name = name; }
+                greet() { return \"Hi \" + this.name; }
+            }
+            var greet = Person(\"Ada\").greet;");
+        assert_eq!(evaluate(&mut interpreter, "greet()").stringify(), "Hi Ada");
+    }
+
+    #[test]
+    fn test_initializer_returns_instance() {
+        let mut interpreter = run("class Point { init() { this.x = 1; return; } }
+            var point = Point();");
+        assert_eq!(
+            evaluate(&mut interpreter, "point.init()").stringify(),
+            "Point instance"
+        );
+    }
+
+    #[test]
+    fn test_class_call_checks_initializer_arity() {
+        match run_err("class Point { init(x, y) {} } Point(1);") {
+            LoxResult::RunTimeError { message, .. } => {
+                assert_eq!(message, "Expected 2 arguments but got 1.")
+            }
+            _ => panic!("Expected runtime error"),
+        }
+    }
+
+    #[test]
+    fn test_undefined_property_is_runtime_error() {
+        assert!(matches!(
+            run_err("class Empty {} Empty().missing;"),
+            LoxResult::RunTimeError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_resolver_rejects_invalid_this_and_initializer_return() {
+        assert!(matches!(
+            run_err("print this;"),
+            LoxResult::ParseError { .. }
+        ));
+        assert!(matches!(
+            run_err("class A { init() { return 1; } }"),
+            LoxResult::ParseError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_subclass_inherits_and_calls_super_methods() {
+        let mut interpreter = run("class Doughnut {
+                cook() { return \"Fry until golden brown.\"; }
+                name() { return \"doughnut\"; }
+            }
+            class BostonCream < Doughnut {
+                cook() { return super.cook() + \" Pipe full of custard.\"; }
+            }
+            var treat = BostonCream();");
+        assert_eq!(
+            evaluate(&mut interpreter, "treat.cook()").stringify(),
+            "Fry until golden brown. Pipe full of custard."
+        );
+        assert_eq!(
+            evaluate(&mut interpreter, "treat.name()").stringify(),
+            "doughnut"
+        );
+    }
+
+    #[test]
+    fn test_super_binds_to_current_instance() {
+        let mut interpreter = run("class A { whoami() { return this.label; } }
+            class B < A { whoami() { return super.whoami(); } }
+            class C < B {}
+            var c = C();
+            c.label = \"c\";");
+        assert_eq!(evaluate(&mut interpreter, "c.whoami()").stringify(), "c");
+    }
+
+    #[test]
+    fn test_invalid_inheritance_is_rejected() {
+        assert!(matches!(
+            run_err("var NotAClass = 1; class A < NotAClass {}"),
+            LoxResult::RunTimeError { .. }
+        ));
+        assert!(matches!(
+            run_err("class A < A {}"),
+            LoxResult::ParseError { .. }
+        ));
+        assert!(matches!(
+            run_err("class A { f() { return super.f(); } }"),
+            LoxResult::ParseError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_clock_native_returns_seconds() {
+        let mut interpreter = run("var start = clock();");
+        assert_eq!(
+            evaluate(&mut interpreter, "type(start)").stringify(),
+            "number"
+        );
+        assert_eq!(
+            evaluate(&mut interpreter, "clock() >= start").stringify(),
+            "true"
+        );
+        assert_eq!(
+            evaluate(&mut interpreter, "clock").stringify(),
+            "<native fn clock>"
+        );
+    }
+
+    #[test]
+    fn test_core_natives_describe_values() {
+        let mut interpreter = run("class A {} fun f() {}");
+        assert_eq!(evaluate(&mut interpreter, "str(12)").stringify(), "12");
+        assert_eq!(evaluate(&mut interpreter, "type(nil)").stringify(), "nil");
+        assert_eq!(
+            evaluate(&mut interpreter, "type(f)").stringify(),
+            "function"
+        );
+        assert_eq!(
+            evaluate(&mut interpreter, "type(A())").stringify(),
+            "instance"
+        );
+    }
+
+    #[test]
+    fn test_native_arity_is_checked() {
+        assert!(matches!(
+            run_err("clock(1);"),
+            LoxResult::RunTimeError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_define_custom_native() {
+        let mut interpreter = Interpreter::new();
+        interpreter.define_native(NativeFunction::new("answer", 0, |_, _| {
+            Ok(Value::Num(42.0))
+        }));
+        assert_eq!(evaluate(&mut interpreter, "answer()").stringify(), "42");
+    }
+
+    #[test]
+    fn test_native_can_raise_runtime_error_at_call_line() {
+        let statements = parse(
+            "var x = 1;
+
+sqrt(-1);",
+        );
+        let mut interpreter = Interpreter::new();
+        interpreter.define_native(NativeFunction::new(
+            "sqrt",
+            1,
+            |arguments, line| match arguments[0] {
+                Value::Num(n) if n >= 0.0 => Ok(Value::Num(n.sqrt())),
+                _ => Err(LoxResult::run_time_error(
+                    line,
+                    "ValueError: math domain error",
+                )),
+            },
+        ));
+        match interpreter.interpret(&statements) {
+            Err(LoxResult::RunTimeError { line, message }) => {
+                assert_eq!(line, 3);
+                assert_eq!(message, "ValueError: math domain error");
+            }
+            _ => panic!("Expected runtime error"),
+        }
+    }
+
+    #[test]
+    fn test_literals_become_runtime_values() {
+        let mut interpreter = run("");
+        assert!(matches!(evaluate(&mut interpreter, "1 + 2"), Value::Num(n) if n == 3.0));
+        assert!(matches!(evaluate(&mut interpreter, "\"a\" + \"b\""), Value::Str(s) if s == "ab"));
+        assert!(matches!(
+            evaluate(&mut interpreter, "true"),
+            Value::Bool(true)
+        ));
+        assert!(matches!(evaluate(&mut interpreter, "nil"), Value::Nil));
+    }
+
+    #[test]
+    fn test_equality_compares_values_of_every_type() {
+        let mut interpreter = run("");
+        for (source, expected) in [
+            ("\"a\" == \"a\"", "true"),
+            ("\"a\" != \"b\"", "true"),
+            ("nil == nil", "true"),
+            ("true == false", "false"),
+            ("true == true", "true"),
+            ("1 == 1", "true"),
+        ] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                expected,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_values_of_different_types_are_unequal() {
+        let mut interpreter = run("");
+        for source in ["1 == \"1\"", "nil == false", "0 == false", "\"\" == nil"] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                "false",
+                "{source}"
+            );
+        }
+        assert_eq!(evaluate(&mut interpreter, "1 != \"1\"").stringify(), "true");
+    }
+
+    #[test]
+    fn test_callables_and_instances_compare_by_identity() {
+        let mut interpreter = run("class A {} var a = A(); var b = A(); fun f() {}");
+        assert_eq!(evaluate(&mut interpreter, "a == a").stringify(), "true");
+        assert_eq!(evaluate(&mut interpreter, "a == b").stringify(), "false");
+        assert_eq!(evaluate(&mut interpreter, "A == A").stringify(), "true");
+        assert_eq!(evaluate(&mut interpreter, "f == f").stringify(), "true");
+        assert_eq!(
+            evaluate(&mut interpreter, "clock == clock").stringify(),
+            "true"
+        );
+    }
+
+    #[test]
+    fn test_only_nil_and_false_are_falsey() {
+        let mut interpreter = run("");
+        for source in ["!nil", "!false"] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                "true",
+                "{source}"
+            );
+        }
+        for source in ["!true", "!0", "!1", "!2", "!\"\"", "!\"text\"", "!clock"] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                "false",
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_conditions_use_lox_truthiness() {
+        let mut interpreter = run("var zero; var empty;
+            if (0) zero = \"truthy\"; else zero = \"falsey\";
+            if (\"\") empty = \"truthy\"; else empty = \"falsey\";");
+        assert_eq!(evaluate(&mut interpreter, "zero").stringify(), "truthy");
+        assert_eq!(evaluate(&mut interpreter, "empty").stringify(), "truthy");
+    }
+
+    #[test]
+    fn test_unary_minus_only_accepts_numbers() {
+        let mut interpreter = run("");
+        assert_eq!(evaluate(&mut interpreter, "-(3)").stringify(), "-3");
+        assert_eq!(evaluate(&mut interpreter, "- -3").stringify(), "3");
+        for source in ["-true;", "-false;", "-nil;", "-\"1\";"] {
+            match run_err(source) {
+                LoxResult::RunTimeError { message, .. } => {
+                    assert!(message.starts_with("TypeError: bad operand type for unary -"))
+                }
+                _ => panic!("Expected runtime error for {source}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_strings_compare_lexicographically() {
+        let mut interpreter = run("");
+        for (source, expected) in [
+            ("\"apple\" < \"banana\"", "true"),
+            ("\"apple\" > \"banana\"", "false"),
+            ("\"app\" < \"apple\"", "true"),
+            ("\"Zed\" < \"alice\"", "true"),
+            ("\"same\" <= \"same\"", "true"),
+            ("\"same\" >= \"same\"", "true"),
+            ("2 >= 10", "false"),
+        ] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                expected,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mixed_type_comparison_is_type_error() {
+        match run_err("1 < \"2\";") {
+            LoxResult::RunTimeError { message, .. } => assert_eq!(
+                message,
+                "TypeError: '<' not supported between 'number' and 'string'"
+            ),
+            _ => panic!("Expected runtime error"),
+        }
+        assert!(matches!(
+            run_err("nil >= nil;"),
+            LoxResult::RunTimeError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_string_interpolation_stringifies_values() {
+        let mut interpreter = run("var name = \"Ada\"; var age = 36;");
+        for (source, expected) in [
+            ("\"Hello ${name}, you are ${age}\"", "Hello Ada, you are 36"),
+            ("\"${age / 8}\"", "4.5"),
+            ("\"${nil} ${true} ${clock}\"", "nil true <native fn clock>"),
+            ("\"outer ${\"inner ${age + 1}\"}\"", "outer inner 37"),
+            ("\"${name}${name}\"", "AdaAda"),
+        ] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                expected,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_string_interpolation_resolves_locals() {
+        let mut interpreter = run("var greeting;
+            {
+                var who = \"local\";
+                fun greet() { return \"hi ${who}\"; }
+                greeting = greet();
+            }");
+        assert_eq!(
+            evaluate(&mut interpreter, "greeting").stringify(),
+            "hi local"
+        );
+    }
+
+    #[test]
+    fn test_list_literals_and_indexing() {
+        let mut interpreter = run("var xs = [1, \"two\", [3, nil], true];");
+        for (source, expected) in [
+            ("xs", "[1, two, [3, nil], true]"),
+            ("[]", "[]"),
+            ("xs[0]", "1"),
+            ("xs[1 + 1][0]", "3"),
+            ("xs[3]", "true"),
+            ("xs[1] = 2.5", "2.5"),
+            ("xs", "[1, 2.5, [3, nil], true]"),
+            ("type(xs)", "list"),
+        ] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                expected,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_lists_have_reference_semantics() {
+        let mut interpreter = run("var a = [1, 2];
+            var b = a;
+            fun push_first(list, value) { list[0] = value; }
+            push_first(b, \"changed\");");
+        assert_eq!(evaluate(&mut interpreter, "a").stringify(), "[changed, 2]");
+        assert_eq!(evaluate(&mut interpreter, "a == b").stringify(), "true");
+        assert_eq!(
+            evaluate(&mut interpreter, "[1] == [1]").stringify(),
+            "false"
+        );
+    }
+
+    #[test]
+    fn test_list_containing_itself_prints_ellipsis() {
+        let mut interpreter = run("var xs = [1];
+            xs[0] = xs;
+            var ys = [xs, xs];");
+        assert_eq!(evaluate(&mut interpreter, "xs").stringify(), "[[...]]");
+        assert_eq!(evaluate(&mut interpreter, "str(xs)").stringify(), "[[...]]");
+        assert_eq!(
+            evaluate(&mut interpreter, "ys").stringify(),
+            "[[[...]], [[...]]]"
+        );
+        assert_eq!(evaluate(&mut interpreter, "xs").to_string(), "[[...]]");
+    }
+
+    #[test]
+    fn test_list_index_errors_report_line() {
+        for (source, expected) in [
+            (
+                "var xs = [1, 2];\nxs[2];",
+                "IndexError: list index 2 out of range for length 2",
+            ),
+            (
+                "var xs = [1, 2];\n\nxs[-1] = 0;",
+                "IndexError: list index -1 out of range for length 2",
+            ),
+            (
+                "var xs = [1, 2];\nxs[0.5];",
+                "TypeError: list indices must be integers, not '0.5'",
+            ),
+            (
+                "var xs = [1, 2];\nxs[\"0\"];",
+                "TypeError: list indices must be integers, not 'string'",
+            ),
+            (
+                "var s = \"abc\";\ns[0];",
+                "TypeError: 'string' is not indexable",
+            ),
+        ] {
+            match run_err(source) {
+                LoxResult::RunTimeError { line, message } => {
+                    assert_eq!(line, source.lines().count(), "{source}");
+                    assert_eq!(message, expected, "{source}");
+                }
+                _ => panic!("Expected runtime error for {source}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_literals_indexing_and_assignment() {
+        let mut interpreter = run("var m = {\"a\": 1, \"b\": [2, 3]};");
+        for (source, expected) in [
+            ("m", "{a: 1, b: [2, 3]}"),
+            ("({})", "{}"),
+            ("m[\"a\"]", "1"),
+            ("m[\"b\"][1]", "3"),
+            ("m[\"c\"] = \"new\"", "new"),
+            ("m[\"a\"] = 10", "10"),
+            ("m", "{a: 10, b: [2, 3], c: new}"),
+            ("keys(m)", "[a, b, c]"),
+            ("len(m)", "3"),
+            ("type(m)", "map"),
+        ] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                expected,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_map_keys_follow_lox_equality() {
+        let mut interpreter = run(
+            "var m = {1: \"number\", \"1\": \"string\", true: \"bool\", nil: \"nil\", 0: \"zero\"};
+            m[1.0] = \"same number\";
+            m[-0] = \"same zero\";",
+        );
+        for (source, expected) in [
+            ("m[1]", "same number"),
+            ("m[\"1\"]", "string"),
+            ("m[true]", "bool"),
+            ("m[nil]", "nil"),
+            ("m[0]", "same zero"),
+            ("len(m)", "5"),
+        ] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                expected,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_maps_have_reference_semantics() {
+        let mut interpreter = run("var config = {};
+            fun configure(map) { map[\"debug\"] = true; }
+            configure(config);");
+        assert_eq!(
+            evaluate(&mut interpreter, "config").stringify(),
+            "{debug: true}"
+        );
+    }
+
+    #[test]
+    fn test_self_containing_maps_print_ellipsis() {
+        let mut interpreter = run("var m = {};
+            m[\"self\"] = m;
+            var xs = [m];
+            m[\"list\"] = xs;");
+        assert_eq!(
+            evaluate(&mut interpreter, "m").stringify(),
+            "{self: {...}, list: [{...}]}"
+        );
+        assert_eq!(
+            evaluate(&mut interpreter, "xs").stringify(),
+            "[{self: {...}, list: [...]}]"
+        );
+    }
+
+    #[test]
+    fn test_len_and_keys_reject_wrong_types() {
+        let mut interpreter = run("");
+        assert_eq!(
+            evaluate(&mut interpreter, "len(\"héllo\")").stringify(),
+            "5"
+        );
+        assert_eq!(evaluate(&mut interpreter, "len([1, 2])").stringify(), "2");
+        for (source, expected) in [
+            ("len(5);", "TypeError: object of type 'number' has no len()"),
+            ("len(nil);", "TypeError: object of type 'nil' has no len()"),
+            (
+                "keys(1);",
+                "TypeError: keys() argument must be a map, not 'number'",
+            ),
+            (
+                "keys([1]);",
+                "TypeError: keys() argument must be a map, not 'list'",
+            ),
+        ] {
+            match run_err(source) {
+                LoxResult::RunTimeError { line, message } => {
+                    assert_eq!(line, 1, "{source}");
+                    assert_eq!(message, expected, "{source}");
+                }
+                _ => panic!("Expected runtime error for {source}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_key_errors() {
+        for (source, expected) in [
+            ("var m = {\"a\": 1};\nm[\"b\"];", "KeyError: b"),
+            (
+                "var m = {};\nm[[1]] = 1;",
+                "TypeError: unhashable type: 'list'",
+            ),
+            (
+                "var m = {};\nm[1e400 - 1e400] = 1;",
+                "TypeError: NaN can't be used as a map key",
+            ),
+        ] {
+            match run_err(source) {
+                LoxResult::RunTimeError { line, message } => {
+                    assert_eq!(line, 2, "{source}");
+                    assert_eq!(message, expected, "{source}");
+                }
+                _ => panic!("Expected runtime error for {source}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_modulo_power_and_integer_division() {
+        let mut interpreter = run("");
+        for (source, expected) in [
+            ("7 % 3", "1"),
+            ("-7 % 3", "2"),
+            ("7 % -3", "-2"),
+            ("5.5 % 2", "1.5"),
+            ("7 ~/ 2", "3"),
+            ("-7 ~/ 2", "-4"),
+            ("7.5 ~/ 2", "3"),
+            ("2 ** 10", "1024"),
+            ("-2 ** 2", "-4"),
+            ("(-2) ** 2", "4"),
+            ("2 ** 3 ** 2", "512"),
+            ("2 ** -1", "0.5"),
+            ("0 ** 0", "1"),
+        ] {
+            assert_eq!(
+                evaluate(&mut interpreter, source).stringify(),
+                expected,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_operators_report_division_by_zero() {
+        for (source, expected) in [
+            ("1 % 0;", "ZerDivisionError: modulo by zero"),
+            ("1 ~/ 0;", "ZerDivisionError: integer division by zero"),
+            (
+                "0 ** -1;",
+                "ZerDivisionError: 0 cannot be raised to a negative power",
+            ),
+            ("\"a\" % 2;", "TypeError: bad operands type for binary %"),
+        ] {
+            match run_err(source) {
+                LoxResult::RunTimeError { message, .. } => {
+                    assert_eq!(message, expected, "{source}")
+                }
+                _ => panic!("Expected runtime error for {source}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_compound_assignment_on_variables() {
+        let mut interpreter = run("var total = 10;
+            total += 5;
+            total -= 3;
+            total *= 2;
+            total /= 8;
+            var greeting = \"Hello\";
+            greeting += \", world\";
+            fun counter() {
+                var count = 0;
+                fun increment() { count += 1; return count; }
+                return increment;
+            }
+            var tick = counter();
+            tick();
+            var ticks = tick();");
+        assert_eq!(evaluate(&mut interpreter, "total").stringify(), "3");
+        assert_eq!(
+            evaluate(&mut interpreter, "greeting").stringify(),
+            "Hello, world"
+        );
+        assert_eq!(evaluate(&mut interpreter, "ticks").stringify(), "2");
+        assert_eq!(evaluate(&mut interpreter, "total += 1").stringify(), "4");
+    }
+
+    #[test]
+    fn test_compound_assignment_evaluates_target_once() {
+        let mut interpreter = run("var calls = 0;
+            class Box { init() { this.value = 1; } }
+            var box = Box();
+            fun getBox() { calls += 1; return box; }
+            var xs = [1, 2, 3];
+            fun at(i) { calls += 1; return i; }
+            var m = {\"hits\": 0};
+            getBox().value += 41;
+            xs[at(1)] *= 10;
+            m[\"hits\"] += 1;");
+        assert_eq!(evaluate(&mut interpreter, "box.value").stringify(), "42");
+        assert_eq!(evaluate(&mut interpreter, "xs").stringify(), "[1, 20, 3]");
+        assert_eq!(evaluate(&mut interpreter, "m").stringify(), "{hits: 1}");
+        assert_eq!(evaluate(&mut interpreter, "calls").stringify(), "2");
+    }
+
+    #[test]
+    fn test_increment_and_decrement_evaluate_target_once() {
+        let mut interpreter = run("var i = 0;
+            var sum = 0;
+            while (i < 4) { sum += i; i++; }
+            var calls = 0;
+            var xs = [10, 20];
+            fun at(n) { calls++; return n; }
+            xs[at(1)]--;
+            ++xs[at(0)];
+            class Counter { init() { this.count = 0; } }
+            var counter = Counter();
+            counter.count++;
+            var returned = ++counter.count;
+            var j = 1;
+            var old = j++;");
+        assert_eq!(evaluate(&mut interpreter, "i").stringify(), "4");
+        assert_eq!(evaluate(&mut interpreter, "sum").stringify(), "6");
+        assert_eq!(evaluate(&mut interpreter, "xs").stringify(), "[11, 19]");
+        assert_eq!(evaluate(&mut interpreter, "calls").stringify(), "2");
+        assert_eq!(evaluate(&mut interpreter, "counter.count").stringify(), "2");
+        assert_eq!(evaluate(&mut interpreter, "returned").stringify(), "2");
+        assert_eq!(evaluate(&mut interpreter, "old").stringify(), "1");
+        assert_eq!(evaluate(&mut interpreter, "j").stringify(), "2");
+        assert_eq!(evaluate(&mut interpreter, "i--").stringify(), "4");
+        assert_eq!(evaluate(&mut interpreter, "i").stringify(), "3");
+        assert_eq!(evaluate(&mut interpreter, "--i").stringify(), "2");
+    }
+
+    #[test]
+    fn test_compound_assignment_errors() {
+        for (source, expected) in [
+            (
+                "var a = \"x\";\na -= 1;",
+                "TypeError: bad operands type for binary -",
+            ),
+            ("var a = 1;\na /= 0;", "ZerDivisionError: division by zero"),
+            (
+                "var xs = [];\nxs[0] += 1;",
+                "IndexError: list index 0 out of range for length 0",
+            ),
+            ("undefined += 1;", "Undefined variable 'undefined'."),
+        ] {
+            match run_err(source) {
+                LoxResult::RunTimeError { message, .. } => {
+                    assert_eq!(message, expected, "{source}")
+                }
+                _ => panic!("Expected runtime error for {source}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_conditional_expression_evaluates_only_chosen_branch() {
+        let mut interpreter = run("var calls = \"\";
+            fun mark(name) { calls += name; return name; }
+            var first = true ? mark(\"a\") : mark(\"b\");
+            var second = nil ? mark(\"c\") : 0 ? mark(\"d\") : mark(\"e\");");
+        assert_eq!(evaluate(&mut interpreter, "first").stringify(), "a");
+        assert_eq!(evaluate(&mut interpreter, "second").stringify(), "d");
+        assert_eq!(evaluate(&mut interpreter, "calls").stringify(), "ad");
+        assert_eq!(
+            evaluate(&mut interpreter, "1 > 2 ? \"yes\" : \"no\"").stringify(),
+            "no"
+        );
+    }
+
+    #[test]
+    fn test_if_else_takes_each_branch() {
+        let mut interpreter = run("var taken = \"\";
+            if (1 < 2) taken = taken + \"then\"; else taken = taken + \"else\";
+            if (1 > 2) taken = taken + \" then\"; else taken = taken + \" else\";
+            if (false) taken = taken + \" skipped\";
+            if (true) if (false) taken = taken + \" inner\"; else taken = taken + \" dangling\";");
+        assert_eq!(
+            evaluate(&mut interpreter, "taken").stringify(),
+            "then else dangling"
+        );
+    }
+
+    #[test]
+    fn test_while_loop_runs_to_completion() {
+        let mut interpreter = run("var i = 0;
+            var sum = 0;
+            while (i < 5) {
+                sum = sum + i;
+                i = i + 1;
+            }
+            var never = \"untouched\";
+            while (false) never = \"ran\";");
+        assert_eq!(evaluate(&mut interpreter, "i").stringify(), "5");
+        assert_eq!(evaluate(&mut interpreter, "sum").stringify(), "10");
+        assert_eq!(evaluate(&mut interpreter, "never").stringify(), "untouched");
+    }
 }
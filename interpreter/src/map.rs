@@ -0,0 +1,99 @@
+use crate::value::Value;
+use result::result::LoxResult;
+use std::collections::{HashMap, HashSet};
+
+/// The hashable form of a map key. Two keys are the same exactly when the
+/// values are `==`, so `1` and `1.0` collide while `1` and `"1"` don't.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum MapKey {
+    Num(u64),
+    Str(String),
+    Bool(bool),
+    Nil,
+}
+
+impl MapKey {
+    fn from_value(key: &Value, line: usize) -> Result<MapKey, LoxResult> {
+        match key {
+            Value::Num(value) if value.is_nan() => Err(LoxResult::run_time_error(
+                line,
+                "TypeError: NaN can't be used as a map key",
+            )),
+            // -0.0 == 0.0 but their bit patterns differ.
+            Value::Num(value) if *value == 0.0 => Ok(MapKey::Num(0.0f64.to_bits())),
+            Value::Num(value) => Ok(MapKey::Num(value.to_bits())),
+            Value::Str(string) => Ok(MapKey::Str(string.clone())),
+            Value::Bool(value) => Ok(MapKey::Bool(*value)),
+            Value::Nil => Ok(MapKey::Nil),
+            _ => Err(LoxResult::run_time_error(
+                line,
+                &format!("TypeError: unhashable type: '{}'", key.type_name()),
+            )),
+        }
+    }
+}
+
+/// A map from strings, numbers, booleans or nil to any value. Entries keep
+/// the order in which their keys were first inserted.
+#[derive(Debug, Default)]
+pub struct LoxMap {
+    entries: Vec<(Value, Value)>,
+    indices: HashMap<MapKey, usize>,
+}
+
+impl LoxMap {
+    pub fn new() -> LoxMap {
+        LoxMap::default()
+    }
+
+    pub fn get(&self, key: &Value, line: usize) -> Result<Value, LoxResult> {
+        match self.indices.get(&MapKey::from_value(key, line)?) {
+            Some(&index) => Ok(self.entries[index].1.clone()),
+            None => Err(LoxResult::run_time_error(
+                line,
+                &format!("KeyError: {}", key.stringify()),
+            )),
+        }
+    }
+
+    /// Overwriting an existing key keeps its original position.
+    pub fn set(&mut self, key: Value, value: Value, line: usize) -> Result<(), LoxResult> {
+        let map_key = MapKey::from_value(&key, line)?;
+        match self.indices.get(&map_key) {
+            Some(&index) => self.entries[index].1 = value,
+            None => {
+                self.indices.insert(map_key, self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn keys(&self) -> Vec<Value> {
+        self.entries.iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    /// Formats the entries as `{key: value, ...}`; see `Value::stringify_with`.
+    pub(crate) fn stringify_with(&self, seen: &mut HashSet<*const ()>) -> String {
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}: {}",
+                    key.stringify_with(seen),
+                    value.stringify_with(seen)
+                )
+            })
+            .collect();
+        format!("{{{}}}", entries.join(", "))
+    }
+}
@@ -0,0 +1,109 @@
+use crate::callable::LoxCallable;
+use crate::interpreter::Interpreter;
+use crate::value::Value;
+use result::result::LoxResult;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Receives the arguments and the line of the call, so a native can raise a
+/// runtime error just like the built-in operators do.
+type NativeFn = dyn Fn(&[Value], usize) -> Result<Value, LoxResult>;
+
+/// A Rust closure exposed to Lox scripts as a callable with a fixed arity.
+pub struct NativeFunction {
+    name: String,
+    arity: usize,
+    function: Box<NativeFn>,
+}
+
+impl NativeFunction {
+    pub fn new(
+        name: &str,
+        arity: usize,
+        function: impl Fn(&[Value], usize) -> Result<Value, LoxResult> + 'static,
+    ) -> NativeFunction {
+        NativeFunction {
+            name: name.to_owned(),
+            arity,
+            function: Box::new(function),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl LoxCallable for NativeFunction {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        line: usize,
+    ) -> Result<Value, LoxResult> {
+        (self.function)(&arguments, line)
+    }
+}
+
+impl fmt::Display for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<native fn {}>", self.name)
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeFunction")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish()
+    }
+}
+
+/// The natives every interpreter starts with in its global scope.
+pub fn core_natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction::new("clock", 0, |_, _| {
+            let seconds = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |elapsed| elapsed.as_secs_f64());
+            Ok(Value::Num(seconds))
+        }),
+        NativeFunction::new("str", 1, |arguments, _| {
+            Ok(Value::Str(arguments[0].stringify()))
+        }),
+        NativeFunction::new("type", 1, |arguments, _| {
+            Ok(Value::Str(arguments[0].type_name().to_owned()))
+        }),
+        // Number of elements, entries or characters.
+        NativeFunction::new("len", 1, |arguments, line| match &arguments[0] {
+            Value::List(elements) => Ok(Value::Num(elements.borrow().len() as f64)),
+            Value::Map(map) => Ok(Value::Num(map.borrow().len() as f64)),
+            Value::Str(string) => Ok(Value::Num(string.chars().count() as f64)),
+            other => Err(LoxResult::run_time_error(
+                line,
+                &format!(
+                    "TypeError: object of type '{}' has no len()",
+                    other.type_name()
+                ),
+            )),
+        }),
+        // A map's keys as a new list, in insertion order.
+        NativeFunction::new("keys", 1, |arguments, line| match &arguments[0] {
+            Value::Map(map) => Ok(Value::List(Rc::new(RefCell::new(map.borrow().keys())))),
+            other => Err(LoxResult::run_time_error(
+                line,
+                &format!(
+                    "TypeError: keys() argument must be a map, not '{}'",
+                    other.type_name()
+                ),
+            )),
+        }),
+    ]
+}
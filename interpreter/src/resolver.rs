@@ -0,0 +1,440 @@
+use crate::interpreter::Interpreter;
+use ast::expr;
+use ast::expr::{Expr, ExprId};
+use ast::stmt;
+use ast::stmt::{FunctionDecl, Stmt};
+use lox_syntax::token::{Object, Token};
+use result::result::LoxResult;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+/// Static pass run between parsing and interpreting that tells the
+/// interpreter how many scopes away each local variable reference lives.
+pub struct Resolver<'a> {
+    interpreter: &'a mut Interpreter,
+    // Maps a name to whether its initializer has finished resolving.
+    scopes: Vec<HashMap<String, bool>>,
+    current_function: FunctionType,
+    current_class: ClassType,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(interpreter: &'a mut Interpreter) -> Resolver<'a> {
+        Resolver {
+            interpreter,
+            scopes: Vec::new(),
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+        }
+    }
+
+    pub fn resolve(&mut self, statements: &[Stmt]) -> Result<(), LoxResult> {
+        for statement in statements {
+            self.resolve_stmt(statement)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_stmt(&mut self, statement: &Stmt) -> Result<(), LoxResult> {
+        statement.accept(self)
+    }
+
+    fn resolve_expr(&mut self, expression: &Expr) -> Result<(), LoxResult> {
+        expression.accept(self)
+    }
+
+    fn resolve_function(
+        &mut self,
+        declaration: &FunctionDecl,
+        function_type: FunctionType,
+    ) -> Result<(), LoxResult> {
+        let enclosing_function = self.current_function;
+        self.current_function = function_type;
+        self.begin_scope();
+        let result = self.resolve_function_body(declaration);
+        self.end_scope();
+        self.current_function = enclosing_function;
+        result
+    }
+
+    fn resolve_function_body(&mut self, declaration: &FunctionDecl) -> Result<(), LoxResult> {
+        for param in &declaration.params {
+            self.declare(param)?;
+            self.define(param);
+        }
+        self.resolve(&declaration.body)
+    }
+
+    fn resolve_local(&mut self, name: &Token, id: ExprId) {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if scope.contains_key(&name.lexeme) {
+                self.interpreter.resolve(id, depth);
+                return;
+            }
+        }
+        // Not found in any local scope, so it is treated as a global.
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token) -> Result<(), LoxResult> {
+        if let Some(scope) = self.scopes.last_mut() {
+            if scope.contains_key(&name.lexeme) {
+                return Err(LoxResult::parse_error(
+                    name.line,
+                    "Already a variable with this name in this scope.",
+                    &name.lexeme,
+                ));
+            }
+            scope.insert(name.lexeme.clone(), false);
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), true);
+        }
+    }
+}
+
+impl expr::Visitor<Result<(), LoxResult>> for Resolver<'_> {
+    fn visit_binary_expr(
+        &mut self,
+        left: &Expr,
+        _operator: &Token,
+        right: &Expr,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(left)?;
+        self.resolve_expr(right)
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<(), LoxResult> {
+        self.resolve_expr(expression)
+    }
+
+    fn visit_interpolation_expr(&mut self, parts: &[Expr]) -> Result<(), LoxResult> {
+        for part in parts {
+            self.resolve_expr(part)?;
+        }
+        Ok(())
+    }
+
+    fn visit_literal_expr(&mut self, _value: &Object) -> Result<(), LoxResult> {
+        Ok(())
+    }
+
+    fn visit_unary_expr(&mut self, _operator: &Token, right: &Expr) -> Result<(), LoxResult> {
+        self.resolve_expr(right)
+    }
+
+    fn visit_assign_expr(
+        &mut self,
+        name: &Token,
+        value: &Expr,
+        id: ExprId,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(value)?;
+        self.resolve_local(name, id);
+        Ok(())
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        callee: &Expr,
+        _paren: &Token,
+        arguments: &[Expr],
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(callee)?;
+        for argument in arguments {
+            self.resolve_expr(argument)?;
+        }
+        Ok(())
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(condition)?;
+        self.resolve_expr(then_branch)?;
+        self.resolve_expr(else_branch)
+    }
+
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        _operator: &Token,
+        value: &Expr,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(value)?;
+        self.resolve_expr(target)
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, _name: &Token) -> Result<(), LoxResult> {
+        self.resolve_expr(object)
+    }
+
+    fn visit_increment_expr(
+        &mut self,
+        target: &Expr,
+        _operator: &Token,
+        _prefix: bool,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(target)
+    }
+
+    fn visit_index_expr(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(object)?;
+        self.resolve_expr(index)
+    }
+
+    fn visit_list_expr(&mut self, elements: &[Expr]) -> Result<(), LoxResult> {
+        for element in elements {
+            self.resolve_expr(element)?;
+        }
+        Ok(())
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        left: &Expr,
+        _operator: &Token,
+        right: &Expr,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(left)?;
+        self.resolve_expr(right)
+    }
+
+    fn visit_map_expr(
+        &mut self,
+        _brace: &Token,
+        entries: &[(Expr, Expr)],
+    ) -> Result<(), LoxResult> {
+        for (key, value) in entries {
+            self.resolve_expr(key)?;
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_set_expr(
+        &mut self,
+        object: &Expr,
+        _name: &Token,
+        value: &Expr,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(value)?;
+        self.resolve_expr(object)
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(object)?;
+        self.resolve_expr(index)?;
+        self.resolve_expr(value)
+    }
+
+    fn visit_super_expr(
+        &mut self,
+        keyword: &Token,
+        _method: &Token,
+        id: ExprId,
+    ) -> Result<(), LoxResult> {
+        let message = match self.current_class {
+            ClassType::None => "Can't use 'super' outside of a class.",
+            ClassType::Class => "Can't use 'super' in a class with no superclass.",
+            ClassType::Subclass => {
+                self.resolve_local(keyword, id);
+                return Ok(());
+            }
+        };
+        Err(LoxResult::parse_error(
+            keyword.line,
+            message,
+            &keyword.lexeme,
+        ))
+    }
+
+    fn visit_this_expr(&mut self, keyword: &Token, id: ExprId) -> Result<(), LoxResult> {
+        if self.current_class == ClassType::None {
+            return Err(LoxResult::parse_error(
+                keyword.line,
+                "Can't use 'this' outside of a class.",
+                &keyword.lexeme,
+            ));
+        }
+        self.resolve_local(keyword, id);
+        Ok(())
+    }
+
+    fn visit_variable_expr(&mut self, name: &Token, id: ExprId) -> Result<(), LoxResult> {
+        if let Some(false) = self.scopes.last().and_then(|scope| scope.get(&name.lexeme)) {
+            return Err(LoxResult::parse_error(
+                name.line,
+                "Can't read local variable in its own initializer.",
+                &name.lexeme,
+            ));
+        }
+        self.resolve_local(name, id);
+        Ok(())
+    }
+}
+
+impl stmt::Visitor<Result<(), LoxResult>> for Resolver<'_> {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<(), LoxResult> {
+        self.begin_scope();
+        let result = self.resolve(statements);
+        self.end_scope();
+        result
+    }
+
+    fn visit_class_stmt(
+        &mut self,
+        name: &Token,
+        superclass: &Option<Box<Expr>>,
+        methods: &[Rc<FunctionDecl>],
+    ) -> Result<(), LoxResult> {
+        if let Some(Expr::Variable(superclass_name, _)) = superclass.as_deref() {
+            if superclass_name.lexeme == name.lexeme {
+                return Err(LoxResult::parse_error(
+                    superclass_name.line,
+                    "A class can't inherit from itself.",
+                    &superclass_name.lexeme,
+                ));
+            }
+        }
+
+        let enclosing_class = self.current_class;
+        self.current_class = ClassType::Class;
+        self.declare(name)?;
+        self.define(name);
+
+        if let Some(superclass) = superclass {
+            self.current_class = ClassType::Subclass;
+            self.resolve_expr(superclass)?;
+            self.begin_scope();
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.insert("super".to_owned(), true);
+            }
+        }
+
+        self.begin_scope();
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert("this".to_owned(), true);
+        }
+        let result = methods.iter().try_for_each(|method| {
+            let function_type = if method.name.lexeme == "init" {
+                FunctionType::Initializer
+            } else {
+                FunctionType::Method
+            };
+            self.resolve_function(method, function_type)
+        });
+        self.end_scope();
+        if superclass.is_some() {
+            self.end_scope();
+        }
+
+        self.current_class = enclosing_class;
+        result
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<(), LoxResult> {
+        self.resolve_expr(expression)
+    }
+
+    fn visit_function_stmt(&mut self, declaration: &Rc<FunctionDecl>) -> Result<(), LoxResult> {
+        // Defined eagerly so the function can refer to itself recursively.
+        self.declare(&declaration.name)?;
+        self.define(&declaration.name);
+        self.resolve_function(declaration, FunctionType::Function)
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: &Option<Box<Stmt>>,
+    ) -> Result<(), LoxResult> {
+        self.resolve_expr(condition)?;
+        self.resolve_stmt(then_branch)?;
+        if let Some(else_branch) = else_branch {
+            self.resolve_stmt(else_branch)?;
+        }
+        Ok(())
+    }
+
+    fn visit_print_stmt(&mut self, value: &Expr) -> Result<(), LoxResult> {
+        self.resolve_expr(value)
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        keyword: &Token,
+        value: &Option<Box<Expr>>,
+    ) -> Result<(), LoxResult> {
+        if let Some(value) = value {
+            if self.current_function == FunctionType::Initializer {
+                return Err(LoxResult::parse_error(
+                    keyword.line,
+                    "Can't return a value from an initializer.",
+                    &keyword.lexeme,
+                ));
+            }
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_var_stmt(
+        &mut self,
+        name: &Token,
+        initializer: &Option<Box<Expr>>,
+    ) -> Result<(), LoxResult> {
+        self.declare(name)?;
+        if let Some(initializer) = initializer {
+            self.resolve_expr(initializer)?;
+        }
+        self.define(name);
+        Ok(())
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<(), LoxResult> {
+        self.resolve_expr(condition)?;
+        self.resolve_stmt(body)
+    }
+}
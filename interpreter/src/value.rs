@@ -0,0 +1,349 @@
+use crate::callable::LoxFunction;
+use crate::class::{LoxClass, LoxInstance};
+use crate::map::LoxMap;
+use crate::native::NativeFunction;
+use lox_syntax::token::Object;
+use result::result::LoxResult;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::Rc;
+
+/// Runtime value produced by the interpreter. Literals from the scanner are
+/// converted into one of these as soon as they are evaluated.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Num(f64),
+    Str(String),
+    Bool(bool),
+    Nil,
+    Function(Rc<LoxFunction>),
+    Class(Rc<LoxClass>),
+    Instance(Rc<RefCell<LoxInstance>>),
+    Native(Rc<NativeFunction>),
+    /// Lists are shared by reference, so aliases see each other's updates.
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<LoxMap>>),
+}
+
+impl From<Object> for Value {
+    fn from(literal: Object) -> Self {
+        match literal {
+            Object::Num(value) => Value::Num(value),
+            Object::Str(string) => Value::Str(string),
+            Object::Nil => Value::Nil,
+            Object::True => Value::Bool(true),
+            Object::False => Value::Bool(false),
+        }
+    }
+}
+
+/// Lox equality: any two values can be compared and values of different
+/// types are simply unequal. Callables and instances compare by identity.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Num(left), Value::Num(right)) => left == right,
+            (Value::Str(left), Value::Str(right)) => left == right,
+            (Value::Bool(left), Value::Bool(right)) => left == right,
+            (Value::Nil, Value::Nil) => true,
+            (Value::Function(left), Value::Function(right)) => Rc::ptr_eq(left, right),
+            (Value::Class(left), Value::Class(right)) => Rc::ptr_eq(left, right),
+            (Value::Instance(left), Value::Instance(right)) => Rc::ptr_eq(left, right),
+            (Value::Native(left), Value::Native(right)) => Rc::ptr_eq(left, right),
+            (Value::List(left), Value::List(right)) => Rc::ptr_eq(left, right),
+            (Value::Map(left), Value::Map(right)) => Rc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Num(value) => write!(f, "{value}"),
+            Value::Str(string) => write!(f, "{string}"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Nil => write!(f, "nil"),
+            Value::Function(function) => write!(f, "{function}"),
+            Value::Class(class) => write!(f, "{class}"),
+            Value::Instance(instance) => write!(f, "{}", instance.borrow()),
+            Value::Native(native) => write!(f, "{native}"),
+            Value::List(_) | Value::Map(_) => write!(f, "{}", self.stringify()),
+        }
+    }
+}
+
+impl Value {
+    pub fn stringify(&self) -> String {
+        self.stringify_with(&mut HashSet::new())
+    }
+
+    /// `seen` holds the containers currently being printed, so a list or map
+    /// that contains itself prints as `[...]` or `{...}` instead of
+    /// recursing forever.
+    pub(crate) fn stringify_with(&self, seen: &mut HashSet<*const ()>) -> String {
+        match self {
+            Value::Num(value) => {
+                let string = value.to_string();
+                match string.strip_suffix(".0") {
+                    Some(integer) => integer.to_string(),
+                    None => string,
+                }
+            }
+            Value::List(elements) => {
+                let address = Rc::as_ptr(elements) as *const ();
+                if !seen.insert(address) {
+                    return "[...]".to_owned();
+                }
+                let elements: Vec<String> = elements
+                    .borrow()
+                    .iter()
+                    .map(|element| element.stringify_with(seen))
+                    .collect();
+                seen.remove(&address);
+                format!("[{}]", elements.join(", "))
+            }
+            Value::Map(map) => {
+                let address = Rc::as_ptr(map) as *const ();
+                if !seen.insert(address) {
+                    return "{...}".to_owned();
+                }
+                let string = map.borrow().stringify_with(seen);
+                seen.remove(&address);
+                string
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    /// Only `nil` and `false` are falsey; every other value is truthy.
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Value::Nil | Value::Bool(false))
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Num(_) => "number",
+            Value::Str(_) => "string",
+            Value::Bool(_) => "boolean",
+            Value::Nil => "nil",
+            Value::Function(_) | Value::Native(_) => "function",
+            Value::Class(_) => "class",
+            Value::Instance(_) => "instance",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+        }
+    }
+
+    pub fn negate(&self, line: usize) -> Result<Self, LoxResult> {
+        match self {
+            Value::Num(value) => Ok(Value::Num(-value)),
+            _ => Err(LoxResult::run_time_error(
+                line,
+                &format!(
+                    "TypeError: bad operand type for unary -: '{}'",
+                    self.type_name()
+                ),
+            )),
+        }
+    }
+
+    pub fn get_index(&self, index: &Value, line: usize) -> Result<Self, LoxResult> {
+        match self {
+            Value::List(elements) => {
+                let elements = elements.borrow();
+                let position = Value::list_position(index, elements.len(), line)?;
+                Ok(elements[position].clone())
+            }
+            Value::Map(map) => map.borrow().get(index, line),
+            _ => Err(LoxResult::run_time_error(
+                line,
+                &format!("TypeError: '{}' is not indexable", self.type_name()),
+            )),
+        }
+    }
+
+    pub fn set_index(&self, index: &Value, value: Value, line: usize) -> Result<(), LoxResult> {
+        match self {
+            Value::List(elements) => {
+                let mut elements = elements.borrow_mut();
+                let position = Value::list_position(index, elements.len(), line)?;
+                elements[position] = value;
+                Ok(())
+            }
+            Value::Map(map) => map.borrow_mut().set(index.clone(), value, line),
+            _ => Err(LoxResult::run_time_error(
+                line,
+                &format!(
+                    "TypeError: '{}' does not support index assignment",
+                    self.type_name()
+                ),
+            )),
+        }
+    }
+
+    /// Checks that `index` is a whole number within `0..len`.
+    fn list_position(index: &Value, len: usize, line: usize) -> Result<usize, LoxResult> {
+        let Value::Num(number) = index else {
+            return Err(LoxResult::run_time_error(
+                line,
+                &format!(
+                    "TypeError: list indices must be integers, not '{}'",
+                    index.type_name()
+                ),
+            ));
+        };
+        if number.fract() != 0.0 {
+            return Err(LoxResult::run_time_error(
+                line,
+                &format!("TypeError: list indices must be integers, not '{number}'"),
+            ));
+        }
+        if *number < 0.0 || *number >= len as f64 {
+            return Err(LoxResult::run_time_error(
+                line,
+                &format!(
+                    "IndexError: list index {} out of range for length {len}",
+                    index.stringify()
+                ),
+            ));
+        }
+        Ok(*number as usize)
+    }
+
+    pub fn add(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        match (left, right) {
+            (Value::Num(left), Value::Num(right)) => Ok(Value::Num(left + right)),
+            (Value::Str(left), Value::Str(right)) => Ok(Value::Str(left + &right)),
+            _ => Err(LoxResult::run_time_error(
+                line,
+                "TypeError: bad operands type for binary +",
+            )),
+        }
+    }
+
+    pub fn subtract(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let (left, right) = Value::numbers(left, right, "-", line)?;
+        Ok(Value::Num(left - right))
+    }
+
+    pub fn multiply(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let (left, right) = Value::numbers(left, right, "*", line)?;
+        Ok(Value::Num(left * right))
+    }
+
+    pub fn divide(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let (left, right) = Value::numbers(left, right, "/", line)?;
+        if right == 0.0 {
+            return Err(LoxResult::run_time_error(
+                line,
+                "ZerDivisionError: division by zero",
+            ));
+        }
+        Ok(Value::Num(left / right))
+    }
+
+    /// Floored division, so the result rounds towards negative infinity.
+    pub fn floor_divide(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let (left, right) = Value::numbers(left, right, "~/", line)?;
+        if right == 0.0 {
+            return Err(LoxResult::run_time_error(
+                line,
+                "ZerDivisionError: integer division by zero",
+            ));
+        }
+        Ok(Value::Num((left / right).floor()))
+    }
+
+    /// The remainder takes the sign of the divisor, matching `~/`.
+    pub fn modulo(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let (left, right) = Value::numbers(left, right, "%", line)?;
+        if right == 0.0 {
+            return Err(LoxResult::run_time_error(
+                line,
+                "ZerDivisionError: modulo by zero",
+            ));
+        }
+        let remainder = left % right;
+        if remainder != 0.0 && (remainder < 0.0) != (right < 0.0) {
+            return Ok(Value::Num(remainder + right));
+        }
+        Ok(Value::Num(remainder))
+    }
+
+    pub fn power(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let (left, right) = Value::numbers(left, right, "**", line)?;
+        if left == 0.0 && right < 0.0 {
+            return Err(LoxResult::run_time_error(
+                line,
+                "ZerDivisionError: 0 cannot be raised to a negative power",
+            ));
+        }
+        Ok(Value::Num(left.powf(right)))
+    }
+
+    pub fn greater(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let ordering = Value::compare(&left, &right, ">", line)?;
+        Ok(Value::Bool(matches!(ordering, Some(Ordering::Greater))))
+    }
+
+    pub fn greater_equal(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let ordering = Value::compare(&left, &right, ">=", line)?;
+        Ok(Value::Bool(matches!(
+            ordering,
+            Some(Ordering::Greater | Ordering::Equal)
+        )))
+    }
+
+    pub fn less(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let ordering = Value::compare(&left, &right, "<", line)?;
+        Ok(Value::Bool(matches!(ordering, Some(Ordering::Less))))
+    }
+
+    pub fn less_equal(left: Self, right: Self, line: usize) -> Result<Self, LoxResult> {
+        let ordering = Value::compare(&left, &right, "<=", line)?;
+        Ok(Value::Bool(matches!(
+            ordering,
+            Some(Ordering::Less | Ordering::Equal)
+        )))
+    }
+
+    /// Orders two numbers numerically or two strings lexicographically.
+    /// `None` means the numbers are unordered, e.g. when one of them is NaN.
+    fn compare(
+        left: &Self,
+        right: &Self,
+        operator: &str,
+        line: usize,
+    ) -> Result<Option<Ordering>, LoxResult> {
+        match (left, right) {
+            (Value::Num(left), Value::Num(right)) => Ok(left.partial_cmp(right)),
+            (Value::Str(left), Value::Str(right)) => Ok(Some(left.cmp(right))),
+            _ => Err(LoxResult::run_time_error(
+                line,
+                &format!(
+                    "TypeError: '{operator}' not supported between '{}' and '{}'",
+                    left.type_name(),
+                    right.type_name()
+                ),
+            )),
+        }
+    }
+
+    fn numbers(
+        left: Self,
+        right: Self,
+        operator: &str,
+        line: usize,
+    ) -> Result<(f64, f64), LoxResult> {
+        match (left, right) {
+            (Value::Num(left), Value::Num(right)) => Ok((left, right)),
+            _ => Err(LoxResult::run_time_error(
+                line,
+                &format!("TypeError: bad operands type for binary {operator}"),
+            )),
+        }
+    }
+}
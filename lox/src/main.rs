@@ -1,4 +1,5 @@
 use interpreter::interpreter::Interpreter;
+use interpreter::resolver::Resolver;
 use lox_syntax::scanner::Scanner;
 use parser::parser::Parser;
 use result::result::LoxResult;
@@ -24,6 +25,7 @@ fn run_file(file_path: &str) -> io::Result<()> {
             LoxResult::Error { .. } => std::process::exit(65),
             LoxResult::RunTimeError { .. } => std::process::exit(70),
             LoxResult::ParseError { .. } => std::process::exit(65),
+            LoxResult::ScanError { .. } => std::process::exit(65),
         },
     }
 }
@@ -50,7 +52,8 @@ fn run(source: &str) -> Result<(), LoxResult> {
     let mut parser = Parser::new(tokens);
     let statements = parser.parse()?;
     let mut interpreter = Interpreter::new();
-    let _ = interpreter.interpret(&statements)?;
+    Resolver::new(&mut interpreter).resolve(&statements)?;
+    interpreter.interpret(&statements)?;
 
     Ok(())
 }
@@ -4,8 +4,13 @@ pub mod parser;
 #[cfg(test)]
 mod tests {
     use super::parser::Parser;
+    use ast::ast_printer::AstPrinter;
+    use ast::expr::Expr;
+    use ast::stmt::Stmt;
+    use lox_syntax::scanner::Scanner;
     use lox_syntax::token::{Token, TokenType};
     use lox_syntax::token::Object;
+    use result::result::LoxResult;
 
     // Helper function to create tokens for testing
     fn make_token(ttype: TokenType, lexeme: &str, literal: Object) -> Token {
@@ -17,9 +22,18 @@ mod tests {
         }
     }
 
+    fn parse_source(source: &str) -> Vec<Stmt> {
+        let tokens = Scanner::new(source).scan_tokens().unwrap();
+        Parser::new(tokens).parse().unwrap()
+    }
+
     #[test]
     fn test_parse_literal() {
-        let tokens = vec![make_token(TokenType::Number, "123", Object::Num(123.0))];
+        let tokens = vec![
+            make_token(TokenType::Number, "123", Object::Num(123.0)),
+            make_token(TokenType::Semicolon, ";", Object::Nil),
+            Token::eof(1),
+        ];
         let mut parser = Parser::new(tokens);
         let result = parser.parse();
         assert!(result.is_ok());
@@ -32,6 +46,8 @@ mod tests {
             make_token(TokenType::Number, "5", Object::Num(5.0)),
             make_token(TokenType::Plus, "+", Object::Nil),
             make_token(TokenType::Number, "7", Object::Num(7.0)),
+            make_token(TokenType::Semicolon, ";", Object::Nil),
+            Token::eof(1),
         ];
         let mut parser = Parser::new(tokens);
         let result = parser.parse();
@@ -39,5 +55,313 @@ mod tests {
         // Add more assertions as needed to validate the parsed expression
     }
 
+    #[test]
+    fn test_parse_if_else() {
+        let statements = parse_source("if (true) print 1; else print 2;");
+        assert_eq!(statements.len(), 1);
+        assert!(matches!(&statements[0], Stmt::If(_, _, Some(_))));
+    }
+
+    #[test]
+    fn test_parse_dangling_else_binds_to_nearest_if() {
+        let statements = parse_source("if (true) if (false) print 1; else print 2;");
+        match &statements[0] {
+            Stmt::If(_, then_branch, None) => {
+                assert!(matches!(**then_branch, Stmt::If(_, _, Some(_))))
+            }
+            _ => panic!("Expected outer if without else branch"),
+        }
+    }
+
+    #[test]
+    fn test_parse_while() {
+        let statements = parse_source("while (a < 3) { a = a + 1; }");
+        match &statements[0] {
+            Stmt::While(_, body) => assert!(matches!(**body, Stmt::Block(_))),
+            _ => panic!("Expected while statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_for_desugars_into_while() {
+        let statements = parse_source("for (var i = 0; i < 3; i = i + 1) print i;");
+        match &statements[0] {
+            Stmt::Block(block) => {
+                assert!(matches!(block[0], Stmt::Variable(..)));
+                match &block[1] {
+                    Stmt::While(_, body) => assert!(matches!(**body, Stmt::Block(_))),
+                    _ => panic!("Expected while loop after initializer"),
+                }
+            }
+            _ => panic!("Expected block wrapping the loop"),
+        }
+    }
+
+    #[test]
+    fn test_parse_for_without_clauses() {
+        let statements = parse_source("for (;;) print 1;");
+        match &statements[0] {
+            Stmt::While(condition, body) => {
+                assert!(matches!(**condition, Expr::Literal(Object::True)));
+                assert!(matches!(**body, Stmt::Print(_)));
+            }
+            _ => panic!("Expected bare while loop"),
+        }
+    }
+
+    #[test]
+    fn test_parse_function_declaration() {
+        let statements = parse_source("fun add(a, b) { print a + b; }");
+        match &statements[0] {
+            Stmt::Function(declaration) => {
+                assert_eq!(declaration.name.lexeme, "add");
+                assert_eq!(declaration.params.len(), 2);
+                assert_eq!(declaration.body.len(), 1);
+            }
+            _ => panic!("Expected function declaration"),
+        }
+    }
+
+    #[test]
+    fn test_parse_chained_calls() {
+        let statements = parse_source("f(1)(2, 3);");
+        match &statements[0] {
+            Stmt::Expression(expr) => match &**expr {
+                Expr::Call(callee, _, arguments) => {
+                    assert_eq!(arguments.len(), 2);
+                    assert!(matches!(**callee, Expr::Call(..)));
+                }
+                _ => panic!("Expected call expression"),
+            },
+            _ => panic!("Expected expression statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_too_many_arguments() {
+        let arguments = vec!["1"; 256].join(", ");
+        let tokens = Scanner::new(&format!("f({arguments});")).scan_tokens().unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_return_at_top_level_is_error() {
+        let tokens = Scanner::new("return 1;").scan_tokens().unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_return_inside_function() {
+        let statements = parse_source("fun f() { return; }");
+        match &statements[0] {
+            Stmt::Function(declaration) => {
+                assert!(matches!(declaration.body[0], Stmt::Return(_, None)))
+            }
+            _ => panic!("Expected function declaration"),
+        }
+    }
+
+    #[test]
+    fn test_parse_class_declaration() {
+        let statements = parse_source("class Point { init(x) { this.x = x; } norm() {} }");
+        match &statements[0] {
+            Stmt::Class(name, None, methods) => {
+                assert_eq!(name.lexeme, "Point");
+                assert_eq!(methods.len(), 2);
+                assert_eq!(methods[0].name.lexeme, "init");
+            }
+            _ => panic!("Expected class declaration"),
+        }
+    }
+
+    #[test]
+    fn test_parse_property_assignment() {
+        let statements = parse_source("a.b.c = 1;");
+        match &statements[0] {
+            Stmt::Expression(expr) => match &**expr {
+                Expr::Set(object, name, _) => {
+                    assert_eq!(name.lexeme, "c");
+                    assert!(matches!(**object, Expr::Get(..)));
+                }
+                _ => panic!("Expected set expression"),
+            },
+            _ => panic!("Expected expression statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_subclass_and_super_call() {
+        let statements = parse_source("class B < A { f() { return super.f(); } }");
+        match &statements[0] {
+            Stmt::Class(_, Some(superclass), methods) => {
+                assert!(matches!(&**superclass, Expr::Variable(name, _) if name.lexeme == "A"));
+                assert!(matches!(methods[0].body[0], Stmt::Return(_, Some(_))));
+            }
+            _ => panic!("Expected subclass declaration"),
+        }
+    }
+
+    #[test]
+    fn test_parse_string_interpolation() {
+        let statements = parse_source("print \"a ${x} b ${y + 1}\";");
+        match &statements[0] {
+            Stmt::Print(expr) => match &**expr {
+                Expr::Interpolation(parts) => {
+                    assert_eq!(parts.len(), 4);
+                    assert!(matches!(&parts[0], Expr::Literal(Object::Str(s)) if s == "a "));
+                    assert!(matches!(&parts[1], Expr::Variable(name, _) if name.lexeme == "x"));
+                    assert!(matches!(&parts[2], Expr::Literal(Object::Str(s)) if s == " b "));
+                    assert!(matches!(&parts[3], Expr::Binary(..)));
+                }
+                _ => panic!("Expected interpolation"),
+            },
+            _ => panic!("Expected print statement"),
+        }
+    }
+
+    fn parse_error_message(source: &str) -> String {
+        let tokens = Scanner::new(source).scan_tokens().unwrap();
+        match Parser::new(tokens).parse() {
+            Err(LoxResult::ParseError { message, .. }) => message,
+            _ => panic!("Expected parse error for {source}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_interpolation_end_is_not_a_string_literal() {
+        assert_eq!(parse_error_message("print \"${a}\" \"x\";"), "Expect ';' after expression.");
+        assert_eq!(parse_error_message("print \"${}\" \"x\";"), "Expect expression");
+    }
+
+    #[test]
+    fn test_parse_empty_interpolation_expects_expression() {
+        assert_eq!(parse_error_message("print \"${}\";"), "Expect expression");
+        assert_eq!(parse_error_message("print \"${a b}\";"), "Expect '}' after interpolated expression.");
+    }
+
+    #[test]
+    fn test_parse_list_literal_and_index_assignment() {
+        let statements = parse_source("xs[0][i + 1] = [1, [2], []];");
+        match &statements[0] {
+            Stmt::Expression(expr) => match &**expr {
+                Expr::SetIndex(object, bracket, index, value) => {
+                    assert!(matches!(**object, Expr::Index(..)));
+                    assert_eq!(bracket.ttype, TokenType::RightBracket);
+                    assert!(matches!(**index, Expr::Binary(..)));
+                    assert!(matches!(&**value, Expr::List(elements) if elements.len() == 3));
+                }
+                _ => panic!("Expected index assignment"),
+            },
+            _ => panic!("Expected expression statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_map_literal_versus_block() {
+        let statements = parse_source("var m = {\"a\": 1, b: [2]}; {\"a\": 1}[\"a\"]; { a; } {}");
+        match &statements[0] {
+            Stmt::Variable(_, Some(initializer)) => {
+                assert!(matches!(&**initializer, Expr::Map(_, entries) if entries.len() == 2));
+            }
+            _ => panic!("Expected variable declaration"),
+        }
+        match &statements[1] {
+            Stmt::Expression(expr) => {
+                assert!(matches!(&**expr, Expr::Index(object, ..) if matches!(**object, Expr::Map(..))));
+            }
+            _ => panic!("Expected expression statement"),
+        }
+        assert!(matches!(&statements[2], Stmt::Block(statements) if statements.len() == 1));
+        assert!(matches!(&statements[3], Stmt::Block(statements) if statements.is_empty()));
+    }
+
+    #[test]
+    fn test_parse_power_precedence_and_associativity() {
+        let mut printer = AstPrinter::new();
+        for (source, expected) in [
+            ("-2 ** 2;", "(- (** 2 2))"),
+            ("2 ** 3 ** 2;", "(** 2 (** 3 2))"),
+            ("2 ** -1;", "(** 2 (- 1))"),
+            ("a * b ** 2 % c ~/ d;", "(~/ (% (* a (** b 2)) c) d)"),
+        ] {
+            match parse_source(source).remove(0) {
+                Stmt::Expression(expr) => assert_eq!(printer.print(*expr), expected, "{source}"),
+                _ => panic!("Expected expression statement"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_compound_assignment() {
+        let mut printer = AstPrinter::new();
+        for (source, expected) in [
+            ("a += 1;", "(+= a 1)"),
+            ("a.b -= c *= 2;", "(-= (. b a) (*= c 2))"),
+            ("xs[i] /= 2;", "(/= ([] xs i) 2)"),
+        ] {
+            match parse_source(source).remove(0) {
+                Stmt::Expression(expr) => assert_eq!(printer.print(*expr), expected, "{source}"),
+                _ => panic!("Expected expression statement"),
+            }
+        }
+        let tokens = Scanner::new("1 += 2;").scan_tokens().unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_increment_and_decrement() {
+        let mut printer = AstPrinter::new();
+        for (source, expected) in [
+            ("a++;", "(a ++)"),
+            ("++a;", "(++ a)"),
+            ("--a.b;", "(-- (. b a))"),
+            ("xs[i]--;", "(([] xs i) --)"),
+            ("-a++;", "(- (a ++))"),
+            ("a - -b;", "(- a (- b))"),
+        ] {
+            match parse_source(source).remove(0) {
+                Stmt::Expression(expr) => assert_eq!(printer.print(*expr), expected, "{source}"),
+                _ => panic!("Expected expression statement"),
+            }
+        }
+        for source in ["1++;", "++f();", "(a)--;"] {
+            let tokens = Scanner::new(source).scan_tokens().unwrap();
+            assert!(Parser::new(tokens).parse().is_err(), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_parse_conditional_expression() {
+        let mut printer = AstPrinter::new();
+        for (source, expected) in [
+            ("a ? b : c;", "(?: a b c)"),
+            ("a ? b : c ? d : e;", "(?: a b (?: c d e))"),
+            ("x = a or b ? y = 1 + 2 : y;", "(= x (?: (or a b) (= y (+ 1 2)) y))"),
+            ("a ? b ? c : d : e;", "(?: a (?: b c d) e)"),
+        ] {
+            match parse_source(source).remove(0) {
+                Stmt::Expression(expr) => assert_eq!(printer.print(*expr), expected, "{source}"),
+                _ => panic!("Expected expression statement"),
+            }
+        }
+        let tokens = Scanner::new("a ? b;").scan_tokens().unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_statement_map_with_multi_token_first_key() {
+        for source in ["{-1: \"a\"}[-1];", "{x.y: 1}[\"k\"];", "{f(a, b): [1], 2: 3}[2];", "{a ? b : c: 1}[b];"] {
+            match &parse_source(source)[0] {
+                Stmt::Expression(expr) => {
+                    assert!(matches!(&**expr, Expr::Index(object, ..) if matches!(**object, Expr::Map(..))), "{source}");
+                }
+                _ => panic!("Expected map expression statement for {source}"),
+            }
+        }
+        for source in ["{ a ? b : c; }", "{ f({1: 2}); }", "{ if (a) { b; } }", "{ var x = [a ? 1 : 2]; }"] {
+            assert!(matches!(&parse_source(source)[0], Stmt::Block(_)), "{source}");
+        }
+    }
+
     // Add more test cases to cover other parsing scenarios
 }
\ No newline at end of file
@@ -1,17 +1,25 @@
-use ast::expr::Expr;
-use ast::stmt::Stmt;
+use ast::expr::{Expr, ExprId};
+use ast::stmt::{FunctionDecl, Stmt};
 use lox_syntax::token::TokenType::*;
 use lox_syntax::token::{Object, Token, TokenType};
 use result::result::LoxResult;
+use std::rc::Rc;
+
+const MAX_ARGUMENTS: usize = 255;
 
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    function_depth: usize,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser { tokens, current: 0 }
+        Parser {
+            tokens,
+            current: 0,
+            function_depth: 0,
+        }
     }
 
     pub fn parse(&mut self) -> Result<Vec<Stmt>, LoxResult> {
@@ -29,13 +37,59 @@ impl Parser {
     }
 
     fn declaration(&mut self) -> Result<Stmt, LoxResult> {
-        if self.matches(&[Var]) {
+        if self.matches(&[Class]) {
+            self.class_declaration()
+        } else if self.matches(&[Fun]) {
+            Ok(Stmt::Function(self.function("function")?))
+        } else if self.matches(&[Var]) {
             self.var_declaration()
         } else {
             self.statement()
         }
     }
 
+    fn class_declaration(&mut self) -> Result<Stmt, LoxResult> {
+        let name = self.consume(Identifier, "Expect class name.")?.clone();
+        let mut superclass = None;
+        if self.matches(&[Less]) {
+            let superclass_name = self.consume(Identifier, "Expect superclass name.")?.clone();
+            superclass = Some(Box::new(Expr::Variable(superclass_name, ExprId::unique())));
+        }
+        self.consume(LeftBrace, "Expect '{' before class body.")?;
+        let mut methods = vec![];
+        while !self.check(RightBrace) && !self.at_end() {
+            methods.push(self.function("method")?);
+        }
+        self.consume(RightBrace, "Expect '}' after class body.")?;
+        Ok(Stmt::Class(name, superclass, methods))
+    }
+
+    fn function(&mut self, kind: &str) -> Result<Rc<FunctionDecl>, LoxResult> {
+        let name = self
+            .consume(Identifier, &format!("Expect {kind} name."))?
+            .clone();
+        self.consume(LeftParen, &format!("Expect '(' after {kind} name."))?;
+        let mut params = vec![];
+        if !self.check(RightParen) {
+            loop {
+                if params.len() >= MAX_ARGUMENTS {
+                    return Err(self.error(self.peek(), "Can't have more than 255 parameters."));
+                }
+                params.push(self.consume(Identifier, "Expect parameter name.")?.clone());
+                if !self.matches(&[Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(RightParen, "Expect ')' after parameters.")?;
+        self.consume(LeftBrace, &format!("Expect '{{' before {kind} body."))?;
+        self.function_depth += 1;
+        let body = self.block();
+        self.function_depth -= 1;
+        let body = body?;
+        Ok(Rc::new(FunctionDecl { name, params, body }))
+    }
+
     fn var_declaration(&mut self) -> Result<Stmt, LoxResult> {
         let name = self.consume(Identifier, "Expect variable name.")?.clone();
         let mut initializer: Option<Box<Expr>> = None;
@@ -48,21 +102,108 @@ impl Parser {
     }
 
     fn statement(&mut self) -> Result<Stmt, LoxResult> {
+        if self.matches(&[For]) {
+            return self.for_statement();
+        }
+        if self.matches(&[If]) {
+            return self.if_statement();
+        }
         if self.matches(&[Print]) {
             return self.print_statement();
         }
-        if self.matches(&[LeftBrace]) {
+        if self.matches(&[Return]) {
+            return self.return_statement();
+        }
+        if self.matches(&[While]) {
+            return self.while_statement();
+        }
+        if self.check(LeftBrace) && !self.map_ahead() {
+            self.advance();
             return Ok(Stmt::Block(self.block()?));
         }
         self.expression_statement()
     }
 
+    fn for_statement(&mut self) -> Result<Stmt, LoxResult> {
+        self.consume(LeftParen, "Expect '(' after 'for'.")?;
+        let initializer = if self.matches(&[Semicolon]) {
+            None
+        } else if self.matches(&[Var]) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if self.check(Semicolon) {
+            Box::new(Expr::Literal(Object::True))
+        } else {
+            self.expression()?
+        };
+        self.consume(Semicolon, "Expect ';' after loop condition.")?;
+
+        let increment = if self.check(RightParen) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        self.consume(RightParen, "Expect ')' after for clauses.")?;
+
+        // Desugar into a while loop wrapped in blocks so the initializer is
+        // scoped to the loop and the increment runs after every iteration.
+        let mut body = self.statement()?;
+        if let Some(increment) = increment {
+            body = Stmt::Block(vec![body, Stmt::Expression(increment)]);
+        }
+        body = Stmt::While(condition, Box::new(body));
+        if let Some(initializer) = initializer {
+            body = Stmt::Block(vec![initializer, body]);
+        }
+        Ok(body)
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, LoxResult> {
+        self.consume(LeftParen, "Expect '(' after 'if'.")?;
+        let condition = self.expression()?;
+        self.consume(RightParen, "Expect ')' after if condition.")?;
+
+        // The else binds to the nearest if, so nested ifs consume it first.
+        let then_branch = Box::new(self.statement()?);
+        let else_branch = if self.matches(&[Else]) {
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+        Ok(Stmt::If(condition, then_branch, else_branch))
+    }
+
     fn print_statement(&mut self) -> Result<Stmt, LoxResult> {
         let value = self.expression()?;
         self.consume(Semicolon, "Expect ';' after expression.")?;
         Ok(Stmt::Print(value))
     }
 
+    fn return_statement(&mut self) -> Result<Stmt, LoxResult> {
+        let keyword = self.previous().clone();
+        if self.function_depth == 0 {
+            return Err(self.error(&keyword, "Can't return from top-level code."));
+        }
+        let value = if self.check(Semicolon) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        self.consume(Semicolon, "Expect ';' after return value.")?;
+        Ok(Stmt::Return(keyword, value))
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, LoxResult> {
+        self.consume(LeftParen, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume(RightParen, "Expect ')' after condition.")?;
+        let body = Box::new(self.statement()?);
+        Ok(Stmt::While(condition, body))
+    }
+
     fn expression_statement(&mut self) -> Result<Stmt, LoxResult> {
         let expr = self.expression()?;
         self.consume(Semicolon, "Expect ';' after expression.")?;
@@ -74,12 +215,21 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> Result<Box<Expr>, LoxResult> {
-        let expr = self.equality()?;
+        let expr = self.conditional()?;
+        if self.matches(&[PlusEqual, MinusEqual, StarEqual, SlashEqual]) {
+            let operator = self.previous().clone();
+            let value = self.assignment()?;
+            return Parser::compound_assign(expr, operator, value);
+        }
         if self.matches(&[Assign]) {
             let equals = self.previous().clone();
             let value = self.assignment()?;
             match *expr {
-                Expr::Variable(name) => Ok(Box::new(Expr::Assign(name, value))),
+                Expr::Variable(name, id) => Ok(Box::new(Expr::Assign(name, value, id))),
+                Expr::Get(object, name) => Ok(Box::new(Expr::Set(object, name, value))),
+                Expr::Index(object, bracket, index) => {
+                    Ok(Box::new(Expr::SetIndex(object, bracket, index, value)))
+                }
                 _ => Err(LoxResult::parse_error(
                     equals.line,
                     "Invalid assignment target.",
@@ -91,6 +241,76 @@ impl Parser {
         }
     }
 
+    fn compound_assign(
+        target: Box<Expr>,
+        operator: Token,
+        value: Box<Expr>,
+    ) -> Result<Box<Expr>, LoxResult> {
+        match *target {
+            Expr::Variable(..) | Expr::Get(..) | Expr::Index(..) => {
+                Ok(Box::new(Expr::CompoundAssign(target, operator, value)))
+            }
+            _ => Err(LoxResult::parse_error(
+                operator.line,
+                "Invalid assignment target.",
+                &operator.lexeme,
+            )),
+        }
+    }
+
+    fn increment(target: Box<Expr>, operator: Token, prefix: bool) -> Result<Box<Expr>, LoxResult> {
+        match *target {
+            Expr::Variable(..) | Expr::Get(..) | Expr::Index(..) => {
+                Ok(Box::new(Expr::Increment(target, operator, prefix)))
+            }
+            _ => Err(LoxResult::parse_error(
+                operator.line,
+                "Invalid assignment target.",
+                &operator.lexeme,
+            )),
+        }
+    }
+
+    /// `?:` sits just above assignment and is right-associative, so
+    /// `a ? b : c ? d : e` reads as `a ? b : (c ? d : e)`.
+    fn conditional(&mut self) -> Result<Box<Expr>, LoxResult> {
+        let condition = self.or()?;
+        if self.matches(&[Question]) {
+            let then_branch = self.expression()?;
+            self.consume(
+                Colon,
+                "Expect ':' after then branch of conditional expression.",
+            )?;
+            let else_branch = self.conditional()?;
+            return Ok(Box::new(Expr::Conditional(
+                condition,
+                then_branch,
+                else_branch,
+            )));
+        }
+        Ok(condition)
+    }
+
+    fn or(&mut self) -> Result<Box<Expr>, LoxResult> {
+        let mut expr = self.and()?;
+        while self.matches(&[Or]) {
+            let operator = self.previous().clone();
+            let right = self.and()?;
+            expr = Box::new(Expr::Logical(expr, operator, right));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Box<Expr>, LoxResult> {
+        let mut expr = self.equality()?;
+        while self.matches(&[And]) {
+            let operator = self.previous().clone();
+            let right = self.equality()?;
+            expr = Box::new(Expr::Logical(expr, operator, right));
+        }
+        Ok(expr)
+    }
+
     fn equality(&mut self) -> Result<Box<Expr>, LoxResult> {
         let mut expr = self.comparison()?;
         while self.matches(&[BangEqual, Equals]) {
@@ -123,7 +343,7 @@ impl Parser {
 
     fn factor(&mut self) -> Result<Box<Expr>, LoxResult> {
         let mut expr = self.unary()?;
-        while self.matches(&[Slash, Star]) {
+        while self.matches(&[Slash, Star, Percent, TildeSlash]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
             expr = Box::new(Expr::Binary(expr, operator, right));
@@ -137,7 +357,70 @@ impl Parser {
             let right = self.unary()?;
             return Ok(Box::new(Expr::Unary(operator, right)));
         }
-        self.primary()
+        if self.matches(&[PlusPlus, MinusMinus]) {
+            let operator = self.previous().clone();
+            let target = self.unary()?;
+            return Parser::increment(target, operator, true);
+        }
+        self.power()
+    }
+
+    /// `**` is right-associative and binds tighter than a unary operator on
+    /// its left, so `-2 ** 2` is `-(2 ** 2)` while `2 ** -1` still parses.
+    fn power(&mut self) -> Result<Box<Expr>, LoxResult> {
+        let expr = self.call()?;
+        if self.matches(&[StarStar]) {
+            let operator = self.previous().clone();
+            let right = self.unary()?;
+            return Ok(Box::new(Expr::Binary(expr, operator, right)));
+        }
+        Ok(expr)
+    }
+
+    fn call(&mut self) -> Result<Box<Expr>, LoxResult> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.matches(&[LeftParen]) {
+                expr = self.finish_call(expr)?;
+            } else if self.matches(&[Dot]) {
+                let name = self
+                    .consume(Identifier, "Expect property name after '.'.")?
+                    .clone();
+                expr = Box::new(Expr::Get(expr, name));
+            } else if self.matches(&[LeftBracket]) {
+                let index = self.expression()?;
+                let bracket = self
+                    .consume(RightBracket, "Expect ']' after index.")?
+                    .clone();
+                expr = Box::new(Expr::Index(expr, bracket, index));
+            } else {
+                break;
+            }
+        }
+        if self.matches(&[PlusPlus, MinusMinus]) {
+            let operator = self.previous().clone();
+            return Parser::increment(expr, operator, false);
+        }
+        Ok(expr)
+    }
+
+    fn finish_call(&mut self, callee: Box<Expr>) -> Result<Box<Expr>, LoxResult> {
+        let mut arguments = vec![];
+        if !self.check(RightParen) {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
+                    return Err(self.error(self.peek(), "Can't have more than 255 arguments."));
+                }
+                arguments.push(*self.expression()?);
+                if !self.matches(&[Comma]) {
+                    break;
+                }
+            }
+        }
+        let paren = self
+            .consume(RightParen, "Expect ')' after arguments.")?
+            .clone();
+        Ok(Box::new(Expr::Call(callee, paren, arguments)))
     }
 
     fn primary(&mut self) -> Result<Box<Expr>, LoxResult> {
@@ -159,8 +442,39 @@ impl Parser {
             )));
         }
 
+        if self.matches(&[Interpolation]) {
+            return self.interpolation();
+        }
+
+        if self.matches(&[Super]) {
+            let keyword = self.previous().clone();
+            self.consume(Dot, "Expect '.' after 'super'.")?;
+            let method = self
+                .consume(Identifier, "Expect superclass method name.")?
+                .clone();
+            return Ok(Box::new(Expr::Super(keyword, method, ExprId::unique())));
+        }
+
+        if self.matches(&[This]) {
+            return Ok(Box::new(Expr::This(
+                self.previous().clone(),
+                ExprId::unique(),
+            )));
+        }
+
         if self.matches(&[Identifier]) {
-            return Ok(Box::new(Expr::Variable(self.previous().clone())));
+            return Ok(Box::new(Expr::Variable(
+                self.previous().clone(),
+                ExprId::unique(),
+            )));
+        }
+
+        if self.matches(&[LeftBracket]) {
+            return self.list();
+        }
+
+        if self.matches(&[LeftBrace]) {
+            return self.map();
         }
 
         if self.matches(&[LeftParen]) {
@@ -172,6 +486,89 @@ impl Parser {
         Err(self.error(self.peek(), "Expect expression"))
     }
 
+    fn list(&mut self) -> Result<Box<Expr>, LoxResult> {
+        let mut elements = vec![];
+        if !self.check(RightBracket) {
+            loop {
+                elements.push(*self.expression()?);
+                if !self.matches(&[Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(RightBracket, "Expect ']' after list elements.")?;
+        Ok(Box::new(Expr::List(elements)))
+    }
+
+    fn map(&mut self) -> Result<Box<Expr>, LoxResult> {
+        let brace = self.previous().clone();
+        let mut entries = vec![];
+        if !self.check(RightBrace) {
+            loop {
+                let key = self.expression()?;
+                self.consume(Colon, "Expect ':' after map key.")?;
+                let value = self.expression()?;
+                entries.push((*key, *value));
+                if !self.matches(&[Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(RightBrace, "Expect '}' after map entries.")?;
+        Ok(Box::new(Expr::Map(brace, entries)))
+    }
+
+    /// A `{` in statement position opens a map rather than a block when its
+    /// first key is followed by a ':', as in `{"a": 1}["a"];`. No statement
+    /// can start with `expr :`, so we look for a ':' before the first `;` or
+    /// the closing `}`, skipping anything nested in brackets and any ':' that
+    /// belongs to a `?`. An empty `{}` is always a block there; wrap it in
+    /// parentheses to get an empty map.
+    fn map_ahead(&self) -> bool {
+        let mut depth = 0;
+        let mut pending_questions = 0;
+        for token in &self.tokens[self.current + 1..] {
+            match token.ttype {
+                LeftParen | LeftBracket | LeftBrace => depth += 1,
+                RightParen | RightBracket | RightBrace if depth > 0 => depth -= 1,
+                RightBrace | Semicolon | Eof if depth == 0 => return false,
+                Question if depth == 0 => pending_questions += 1,
+                Colon if depth == 0 && pending_questions > 0 => pending_questions -= 1,
+                Colon if depth == 0 => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Parses the rest of an interpolated string once its first segment has
+    /// been matched: every `${...}` expression is followed either by another
+    /// `Interpolation` segment or by the closing `InterpolationEnd` segment.
+    fn interpolation(&mut self) -> Result<Box<Expr>, LoxResult> {
+        let mut parts = vec![];
+        loop {
+            self.push_segment(&mut parts);
+            parts.push(*self.expression()?);
+            if !self.matches(&[Interpolation]) {
+                break;
+            }
+        }
+        self.consume(
+            InterpolationEnd,
+            "Expect '}' after interpolated expression.",
+        )?;
+        self.push_segment(&mut parts);
+        Ok(Box::new(Expr::Interpolation(parts)))
+    }
+
+    fn push_segment(&self, parts: &mut Vec<Expr>) {
+        if let Some(Object::Str(segment)) = &self.previous().literal {
+            if !segment.is_empty() {
+                parts.push(Expr::Literal(Object::Str(segment.clone())));
+            }
+        }
+    }
+
     fn block(&mut self) -> Result<Vec<Stmt>, LoxResult> {
         let mut statements = vec![];
         while !self.check(RightBrace) && !self.at_end() {
@@ -245,4 +642,3 @@ impl Parser {
         }
     }
 }
-
@@ -4,7 +4,8 @@
 pub enum LoxResult{
     Error {line: usize, message: String},
     RunTimeError {line: usize, message: String},
-    ParseError {line: usize, lexeme: String, message: String}
+    ParseError {line: usize, lexeme: String, message: String},
+    ScanError {line: usize, column: usize, message: String}
 }
 
 impl LoxResult{
@@ -18,7 +19,7 @@ impl LoxResult{
     }
 
     pub fn parse_error(line: usize, message: &str, lexeme: &str) -> LoxResult {
-        let lexeme_location= if lexeme == "" {"end"} else {lexeme};
+        let lexeme_location= if lexeme.is_empty() {"end"} else {lexeme};
         let err = LoxResult::ParseError {
             line,
             message: message.to_string(),
@@ -28,6 +29,16 @@ impl LoxResult{
         err
     }
 
+    pub fn scan_error(line: usize, column: usize, message: &str) -> LoxResult {
+        let err = LoxResult::ScanError {
+            line,
+            column,
+            message: message.to_string()
+        };
+        err.report();
+        err
+    }
+
     pub fn run_time_error(line: usize, message: &str) -> LoxResult {
         let err = LoxResult::RunTimeError {
             line,
@@ -41,7 +52,8 @@ impl LoxResult{
         match self {
             LoxResult::Error {line, message} => eprintln!(" Error [line {line}]: {message}"),
             LoxResult::RunTimeError { line, message } => eprintln!("Runtime error [line {line}]: {message} "),
-            LoxResult::ParseError {line, lexeme, message} => eprintln!("Parse error [line {line}]: {message} at {lexeme}")
+            LoxResult::ParseError {line, lexeme, message} => eprintln!("Parse error [line {line}]: {message} at {lexeme}"),
+            LoxResult::ScanError {line, column, message} => eprintln!(" Error [line {line}, column {column}]: {message}")
         }
     }
 }
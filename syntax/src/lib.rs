@@ -8,6 +8,7 @@ mod test {
         scanner::Scanner,
         token::{TokenType, Token, Object}
     };
+    use result::result::LoxResult;
 
   #[test]
     fn test_scan_tokens_single_token() {
@@ -20,7 +21,7 @@ mod test {
     fn test_scan_tokens_multiple_tokens() {
         let mut scanner = Scanner::new("(2.2)");
         let scanned_tokens = scanner.scan_tokens().unwrap();
-        let vec_tokens = vec![
+        let vec_tokens = [
             Token::new(TokenType::LeftParen, "(".to_string(), None, 2),
             Token::new(TokenType::Number, "2.2".to_string(), Some(Object::Num(2.2)), 2),
             Token::new(TokenType::RightParen, ")".to_string(), None, 2),
@@ -54,4 +55,199 @@ mod test {
             assert_eq!(token.ttype, vec_tokens[index].ttype);
         }
     }
+
+    fn scan_string(source: &str) -> String {
+        let mut scanner = Scanner::new(source);
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        match &scanned_tokens[0].literal {
+            Some(Object::Str(value)) => value.clone(),
+            _ => panic!("Expected string literal"),
+        }
+    }
+
+    #[test]
+    fn test_scan_string_escape_sequences() {
+        assert_eq!(scan_string(r#""a\nb\tc""#), "a\nb\tc");
+        assert_eq!(scan_string(r#""say \"hi\"""#), "say \"hi\"");
+        assert_eq!(scan_string(r#""back\\slash""#), "back\\slash");
+        assert_eq!(scan_string(r#""\u{48}\u{e9}\u{1F600}""#), "H\u{e9}\u{1F600}");
+    }
+
+    #[test]
+    fn test_scan_invalid_escape_points_at_column() {
+        let mut scanner = Scanner::new("var s =\n  \"ok \\q\";");
+        match scanner.scan_tokens() {
+            Err(LoxResult::ScanError { line, column, .. }) => {
+                assert_eq!(line, 2);
+                assert_eq!(column, 7);
+            }
+            _ => panic!("Expected scan error"),
+        }
+    }
+
+    #[test]
+    fn test_scan_invalid_unicode_escapes() {
+        for source in [r#""\u{}""#, r#""\u{110000}""#, r#""\u41""#, r#""\u{1234567}""#] {
+            let mut scanner = Scanner::new(source);
+            assert!(
+                matches!(scanner.scan_tokens(), Err(LoxResult::ScanError { column: 2, .. })),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_scan_multiline_string_counts_lines() {
+        let mut scanner = Scanner::new("\"one\ntwo\" x");
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        assert_eq!(scanned_tokens[1].line, 2);
+    }
+
+    #[test]
+    fn test_scan_string_interpolation_splits_segments() {
+        let mut scanner = Scanner::new(r#""Hello ${name}, you are ${ {age} }!""#);
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        let types: Vec<TokenType> = scanned_tokens.iter().map(|token| token.ttype).collect();
+        assert_eq!(types, [
+            TokenType::Interpolation,
+            TokenType::Identifier,
+            TokenType::Interpolation,
+            TokenType::LeftBrace,
+            TokenType::Identifier,
+            TokenType::RightBrace,
+            TokenType::InterpolationEnd,
+            TokenType::Eof,
+        ]);
+        assert!(matches!(&scanned_tokens[0].literal, Some(Object::Str(s)) if s == "Hello "));
+        assert!(matches!(&scanned_tokens[2].literal, Some(Object::Str(s)) if s == ", you are "));
+        assert!(matches!(&scanned_tokens[6].literal, Some(Object::Str(s)) if s == "!"));
+    }
+
+    #[test]
+    fn test_scan_escaped_dollar_is_not_interpolated() {
+        assert_eq!(scan_string(r#""cost: \${x} $5""#), "cost: ${x} $5");
+    }
+
+    #[test]
+    fn test_scan_unterminated_interpolation() {
+        let mut scanner = Scanner::new("var a;\nprint \"Hello ${name;\n");
+        match scanner.scan_tokens() {
+            Err(LoxResult::ScanError { line, column, .. }) => {
+                assert_eq!(line, 2);
+                assert_eq!(column, 14);
+            }
+            _ => panic!("Expected scan error"),
+        }
+    }
+
+    fn scan_number(source: &str) -> f64 {
+        let mut scanner = Scanner::new(source);
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        assert_eq!(scanned_tokens[0].lexeme, source);
+        match scanned_tokens[0].literal {
+            Some(Object::Num(value)) => value,
+            _ => panic!("Expected number literal"),
+        }
+    }
+
+    #[test]
+    fn test_scan_extended_number_literals() {
+        assert_eq!(scan_number("0xFF"), 255.0);
+        assert_eq!(scan_number("0Xff_ff"), 65535.0);
+        assert_eq!(scan_number("0b1010"), 10.0);
+        assert_eq!(scan_number("0b1111_0000"), 240.0);
+        assert_eq!(scan_number("1e-9"), 1e-9);
+        assert_eq!(scan_number("2.5E+3"), 2500.0);
+        assert_eq!(scan_number("1_000_000"), 1_000_000.0);
+        assert_eq!(scan_number("12.345_6"), 12.3456);
+        assert_eq!(scan_number("0"), 0.0);
+    }
+
+    #[test]
+    fn test_scan_number_followed_by_method_call() {
+        let mut scanner = Scanner::new("1.5.abs");
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        let types: Vec<TokenType> = scanned_tokens.iter().map(|token| token.ttype).collect();
+        assert_eq!(types, [TokenType::Number, TokenType::Dot, TokenType::Identifier, TokenType::Eof]);
+    }
+
+    #[test]
+    fn test_scan_malformed_numbers_point_at_column() {
+        for (source, expected_column) in [
+            ("x = 0x;", 7),
+            ("x = 0b102;", 9),
+            ("x = 0xFG;", 8),
+            ("x = 1e;", 6),
+            ("x = 1e+;", 6),
+            ("x = 1__0;", 6),
+            ("x = 100_;", 8),
+            ("x = 1_.5;", 6),
+            ("x = 0x_1;", 7),
+            ("x = 1.2.3;", 8),
+            ("x = 12abc;", 7),
+        ] {
+            let mut scanner = Scanner::new(source);
+            match scanner.scan_tokens() {
+                Err(LoxResult::ScanError { line: 1, column, .. }) => {
+                    assert_eq!(column, expected_column, "{source}")
+                }
+                _ => panic!("Expected scan error for {source}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan_line_comment_at_end_of_file() {
+        let mut scanner = Scanner::new("1 // trailing");
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        assert_eq!(scanned_tokens.len(), 2);
+    }
+
+    #[test]
+    fn test_scan_nested_block_comments() {
+        let mut scanner = Scanner::new("1 /* outer /* inner\n */ still\n comment */ / 2");
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        let types: Vec<TokenType> = scanned_tokens.iter().map(|token| token.ttype).collect();
+        assert_eq!(types, [TokenType::Number, TokenType::Slash, TokenType::Number, TokenType::Eof]);
+        assert_eq!(scanned_tokens[1].line, 3);
+    }
+
+    #[test]
+    fn test_scan_unterminated_block_comment() {
+        let mut scanner = Scanner::new("var a;\n  /* one /* two */\n");
+        match scanner.scan_tokens() {
+            Err(LoxResult::ScanError { line, column, .. }) => {
+                assert_eq!(line, 2);
+                assert_eq!(column, 3);
+            }
+            _ => panic!("Expected scan error"),
+        }
+    }
+
+    #[test]
+    fn test_scan_unicode_identifiers() {
+        let mut scanner = Scanner::new("var größe = straße_2 + имя + 変数 + _x + café_π;");
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        let identifiers: Vec<&str> = scanned_tokens
+            .iter()
+            .filter(|token| token.ttype == TokenType::Identifier)
+            .map(|token| token.lexeme.as_str())
+            .collect();
+        assert_eq!(identifiers, ["größe", "straße_2", "имя", "変数", "_x", "café_π"]);
+    }
+
+    #[test]
+    fn test_scan_identifier_uses_xid_rules() {
+        // Combining marks and digits may continue an identifier but not start one.
+        let mut scanner = Scanner::new("e\u{301}te\u{301}");
+        let scanned_tokens = scanner.scan_tokens().unwrap();
+        assert_eq!(scanned_tokens[0].ttype, TokenType::Identifier);
+        assert_eq!(scanned_tokens[0].lexeme, "e\u{301}te\u{301}");
+        assert!(Scanner::new("\u{301}e").scan_tokens().is_err());
+
+        // Symbols such as emoji or `€` are not identifier characters at all.
+        let mut scanner = Scanner::new("a€");
+        assert!(scanner.scan_tokens().is_err());
+        assert!(Scanner::new("😀").scan_tokens().is_err());
+    }
 }
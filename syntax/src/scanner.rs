@@ -1,12 +1,23 @@
 use result::result::LoxResult;
 use crate::token::{Token, TokenType, Object};
 
+/// A `${` whose matching `}` hasn't been reached yet.
+struct OpenInterpolation {
+    // Unmatched '{' inside the embedded expression.
+    braces: usize,
+    line: usize,
+    column: usize,
+}
+
 pub struct Scanner {
     source: Vec<char>,
     tokens: Vec<Token>,
     start: usize,
     current: usize,
     line: usize,
+    line_start: usize,
+    // Every string interpolation we are inside of, innermost last.
+    interpolations: Vec<OpenInterpolation>,
 }
 
 impl Scanner{
@@ -16,13 +27,15 @@ impl Scanner{
             tokens: Vec::new(),
             start: 0,
             current: 0,
-            line: 1
+            line: 1,
+            line_start: 0,
+            interpolations: Vec::new()
         }
     }
 
     pub fn scan_tokens(&mut self) -> Result<Vec<Token>, LoxResult> {
         let mut had_error: Option<LoxResult> = None;
-        while self.at_end() == false{
+        while !self.at_end() {
             self.start = self.current;
             match self.scan_token() {
                 Ok(_) => {},
@@ -31,6 +44,9 @@ impl Scanner{
                 }
             }
         };
+        if let Some(open) = self.interpolations.last() {
+            had_error = Some(LoxResult::scan_error(open.line, open.column, "Unterminated string interpolation."));
+        }
 
         self.tokens.push(Token::eof(self.line));
 
@@ -41,37 +57,70 @@ impl Scanner{
     }
 
     fn scan_token(&mut self) -> Result<(),LoxResult>{
-        let character = self.advance();
+        let character = *self.advance();
         match character {
             '(' => {self.add_token(TokenType::LeftParen)},
             ')' => {self.add_token(TokenType::RightParen)},
-            '{' => {self.add_token(TokenType::LeftBrace)},
-            '}' => {self.add_token(TokenType::RightBrace)},
+            '{' => {self.left_brace()},
+            '}' => {self.right_brace()?},
+            '[' => {self.add_token(TokenType::LeftBracket)},
+            ']' => {self.add_token(TokenType::RightBracket)},
+            ':' => {self.add_token(TokenType::Colon)},
+            '?' => {self.add_token(TokenType::Question)},
             ',' => {self.add_token(TokenType::Comma)},
             '.' => {self.add_token(TokenType::Dot)},
-            '-' => {self.add_token(TokenType::Minus)},
-            '+' => {self.add_token(TokenType::Plus)},
+            '-' => {
+                if self.expect('-') {
+                    self.add_token(TokenType::MinusMinus)
+                } else if self.expect('=') {
+                    self.add_token(TokenType::MinusEqual)
+                } else {
+                    self.add_token(TokenType::Minus)
+                }
+            }
+            '+' => {
+                if self.expect('+') {
+                    self.add_token(TokenType::PlusPlus)
+                } else if self.expect('=') {
+                    self.add_token(TokenType::PlusEqual)
+                } else {
+                    self.add_token(TokenType::Plus)
+                }
+            }
             ';' => {self.add_token(TokenType::Semicolon)},
-            '*' => {self.add_token(TokenType::Star)},
+            '*' => {
+                if self.expect('*') {
+                    self.add_token(TokenType::StarStar)
+                } else if self.expect('=') {
+                    self.add_token(TokenType::StarEqual)
+                } else {
+                    self.add_token(TokenType::Star)
+                }
+            }
+            '%' => {self.add_token(TokenType::Percent)},
+            // Integer division; `//` already starts a comment.
+            '~' if self.expect('/') => {self.add_token(TokenType::TildeSlash)},
             '!' => {if self.expect('='){self.add_token(TokenType::BangEqual)} else {self.add_token(TokenType::Bang)}},
             '=' => {if self.expect('='){self.add_token(TokenType::Equals)} else {self.add_token(TokenType::Assign)}},
             '<' => {if self.expect('='){self.add_token(TokenType::LessEqual)} else {self.add_token(TokenType::Less)}},
             '>' => {if self.expect('='){self.add_token(TokenType::GreaterEqual)} else {self.add_token(TokenType::Greater)}}
-            '"' => {self.string()?}
+            '"' => {self.string(TokenType::String)?}
             '0'..='9' => {self.number()?}
-            'a'..='z' | 'A'..='Z' | '_' => {self.identifier()?}
+            ch if Scanner::is_identifier_start(ch) => {self.identifier()?}
 
-            '\n' => {self.line += 1}
+            '\n' => {self.new_line()}
             '\r' => {},
             '\t' => {},
             ' ' => {},
             '/' => {
-                match self.expect('/'){
-                    true => {
-                        while self.peek().unwrap() != '\n' && !self.at_end() {self.advance();}
-                    }
-                    false => self.add_token(TokenType::Slash)
-
+                if self.expect('/') {
+                    while self.peek().is_some_and(|ch| ch != '\n') {self.advance();}
+                } else if self.expect('*') {
+                    self.block_comment()?
+                } else if self.expect('=') {
+                    self.add_token(TokenType::SlashEqual)
+                } else {
+                    self.add_token(TokenType::Slash)
                 }
             }
             _  => {return Err(LoxResult::error(self.line, "Unexpected character."))}
@@ -80,6 +129,16 @@ impl Scanner{
     }
 
 
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
+    /// 1-based column of the character at `index` in the current line.
+    fn column(&self, index: usize) -> usize {
+        index - self.line_start + 1
+    }
+
     fn add_token(&mut self, ttype: TokenType) {
         self.add_token_object(ttype, None );
     }
@@ -114,67 +173,294 @@ impl Scanner{
         }
     }
 
-    fn is_alphanumeric(ch: Option<char>) -> bool {
-        if let Some(ch) = ch {
-            ch.is_alphanumeric()
-        } else {
-            false
-        }
+    /// Identifiers follow Unicode `XID_Start XID_Continue*`, with `_` also
+    /// allowed as the first character.
+    fn is_identifier_start(ch: char) -> bool {
+        ch == '_' || unicode_ident::is_xid_start(ch)
+    }
+
+    fn is_identifier_continue(ch: Option<char>) -> bool {
+        ch.is_some_and(unicode_ident::is_xid_continue)
     }
 
     fn expect(&mut self, expected: char) -> bool{
         match self.source.get(self.current){
-            Some(ch) => {
-                if expected == *ch {
-                    self.current += 1;
-                    true
-                } else {
-                    false
+            Some(ch) if expected == *ch => {
+                self.current += 1;
+                true
+            }
+            _ => false
+        }
+    }
+
+    /// Skips a `/* ... */` comment whose opening has been consumed. Block
+    /// comments nest, so every `/*` inside needs its own `*/`.
+    fn block_comment(&mut self) -> Result<(), LoxResult> {
+        let (line, column) = (self.line, self.column(self.start));
+        let mut depth = 1;
+        while depth > 0 {
+            match (self.peek(), self.peek_next()) {
+                (None, _) => {
+                    return Err(LoxResult::scan_error(line, column, "Unterminated block comment."))
+                }
+                (Some('/'), Some('*')) => {
+                    self.current += 2;
+                    depth += 1;
+                }
+                (Some('*'), Some('/')) => {
+                    self.current += 2;
+                    depth -= 1;
+                }
+                (Some('\n'), _) => {
+                    self.advance();
+                    self.new_line();
+                }
+                _ => {
+                    self.advance();
                 }
             }
-            None => false
         }
+        Ok(())
+    }
+
+    fn left_brace(&mut self) {
+        if let Some(open) = self.interpolations.last_mut() {
+            open.braces += 1;
+        }
+        self.add_token(TokenType::LeftBrace);
     }
 
-    fn string(&mut self) -> Result<(), LoxResult>{
-        while let Some(ch) = self.peek(){
-            match ch {
-                '\n' => {self.line += 1},
-                '"' => break,
-                 _ => {self.advance();}
+    fn right_brace(&mut self) -> Result<(), LoxResult> {
+        match self.interpolations.last_mut() {
+            // Closes the `${` of an interpolation, so the string carries on.
+            Some(open) if open.braces == 0 => {
+                self.interpolations.pop();
+                self.string(TokenType::InterpolationEnd)
+            }
+            Some(open) => {
+                open.braces -= 1;
+                self.add_token(TokenType::RightBrace);
+                Ok(())
+            }
+            None => {
+                self.add_token(TokenType::RightBrace);
+                Ok(())
             }
         }
-        if self.at_end(){
-            return Err(LoxResult::error(self.line, "String not ended"));
+    }
+
+    /// Scans a string up to its closing quote, emitting `closing`, or up to
+    /// the next `${` in which case an `Interpolation` token is emitted and
+    /// the scanner goes back to regular tokens until the matching `}`.
+    fn string(&mut self, closing: TokenType) -> Result<(), LoxResult>{
+        let mut value = String::new();
+        let mut had_error: Option<LoxResult> = None;
+        loop {
+            match self.peek() {
+                None => return Err(LoxResult::error(self.line, "String not ended")),
+                Some('"') => break,
+                Some('$') if self.peek_next() == Some('{') => {
+                    let column = self.column(self.current);
+                    self.advance();
+                    self.advance();
+                    self.interpolations.push(OpenInterpolation { braces: 0, line: self.line, column });
+                    if let Some(e) = had_error {
+                        return Err(e);
+                    }
+                    self.add_token_object(TokenType::Interpolation, Some(Object::Str(value)));
+                    return Ok(());
+                }
+                Some('\\') => {
+                    // Keep scanning to the closing quote so one bad escape
+                    // doesn't turn the rest of the string into tokens.
+                    match self.escape() {
+                        Ok(ch) => value.push(ch),
+                        Err(e) => had_error = had_error.or(Some(e)),
+                    }
+                }
+                Some(ch) => {
+                    self.advance();
+                    if ch == '\n' {
+                        self.new_line();
+                    }
+                    value.push(ch);
+                }
+            }
         }
         self.advance();
-        let value = self.source[self.start + 1..self.current - 1].iter().collect();
-        self.add_token_object(TokenType::String, Some(Object::Str(value)));
+        if let Some(e) = had_error {
+            return Err(e);
+        }
+        self.add_token_object(closing, Some(Object::Str(value)));
         Ok(())
     }
 
+    fn escape(&mut self) -> Result<char, LoxResult> {
+        let column = self.column(self.current);
+        self.advance();
+        let escaped = match self.peek() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('\\') => '\\',
+            Some('"') => '"',
+            Some('$') => '$',
+            Some('u') => {
+                self.advance();
+                return self.unicode_escape(column);
+            }
+            Some(ch) => {
+                return Err(LoxResult::scan_error(
+                    self.line,
+                    column,
+                    &format!("Invalid escape sequence '\\{ch}'."),
+                ))
+            }
+            None => {
+                return Err(LoxResult::scan_error(
+                    self.line,
+                    column,
+                    "Unterminated escape sequence.",
+                ))
+            }
+        };
+        self.advance();
+        Ok(escaped)
+    }
+
+    fn unicode_escape(&mut self, column: usize) -> Result<char, LoxResult> {
+        let invalid = |scanner: &Scanner| {
+            LoxResult::scan_error(
+                scanner.line,
+                column,
+                "Invalid unicode escape, expected '\\u{' followed by 1 to 6 hex digits and '}'.",
+            )
+        };
+        if !self.expect('{') {
+            return Err(invalid(self));
+        }
+        let digits_start = self.current;
+        while self.peek().is_some_and(|ch| ch.is_ascii_hexdigit()) {
+            self.advance();
+        }
+        let digits: String = self.source[digits_start..self.current].iter().collect();
+        if digits.is_empty() || digits.len() > 6 || !self.expect('}') {
+            return Err(invalid(self));
+        }
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| {
+                LoxResult::scan_error(
+                    self.line,
+                    column,
+                    &format!("Invalid unicode code point '\\u{{{digits}}}'."),
+                )
+            })
+    }
+
 
     fn number(&mut self) -> Result<(), LoxResult>{
-        while Scanner::is_digit(self.peek()){
+        let radix = match (self.source[self.start], self.peek()) {
+            ('0', Some('x' | 'X')) => 16,
+            ('0', Some('b' | 'B')) => 2,
+            _ => 10,
+        };
+        let number = if radix == 10 {
+            self.decimal()?
+        } else {
             self.advance();
+            self.integer(radix)?
+        };
+        // A number must not run straight into a letter, e.g. `0xFG` or `12abc`.
+        if let Some(ch) = self.peek().filter(|&ch| Scanner::is_identifier_continue(Some(ch))) {
+            return Err(LoxResult::scan_error(
+                self.line,
+                self.column(self.current),
+                &format!("Unexpected '{ch}' in number literal."),
+            ));
         }
+        self.add_token_object(TokenType::Number, Some(Object::Num(number)));
+        Ok(())
+    }
+
+    /// Scans `123`, `1_000.5` or `1e-9` from the start of the token.
+    fn decimal(&mut self) -> Result<f64, LoxResult> {
+        self.current = self.start;
+        self.digits(10)?;
         if self.peek() == Some('.') && Scanner::is_digit(self.peek_next()) {
             self.advance();
-            while Scanner::is_digit(self.peek()){
+            self.digits(10)?;
+        }
+        if let Some('e' | 'E') = self.peek() {
+            let column = self.column(self.current);
+            self.advance();
+            if let Some('+' | '-') = self.peek() {
                 self.advance();
-                if self.peek() == Some('.') {
-                    return Err(LoxResult::error(self.line, "Number with multiple dots"));
+            }
+            if !Scanner::is_digit(self.peek()) {
+                return Err(LoxResult::scan_error(self.line, column, "Exponent has no digits."));
+            }
+            self.digits(10)?;
+        }
+        if self.peek() == Some('.') && Scanner::is_digit(self.peek_next()) {
+            return Err(LoxResult::scan_error(
+                self.line,
+                self.column(self.current),
+                "Number literal can't have a second decimal point.",
+            ));
+        }
+        let value: String = self.source[self.start..self.current]
+            .iter()
+            .filter(|&&ch| ch != '_')
+            .collect();
+        Ok(value.parse().unwrap())
+    }
+
+    /// Scans the digits of a `0x` or `0b` literal, the prefix already consumed.
+    fn integer(&mut self, radix: u32) -> Result<f64, LoxResult> {
+        let digits_start = self.current;
+        if self.digits(radix)? == 0 {
+            let (name, prefix) = if radix == 16 { ("hexadecimal", "0x") } else { ("binary", "0b") };
+            return Err(LoxResult::scan_error(
+                self.line,
+                self.column(self.current),
+                &format!("Expected {name} digits after '{prefix}'."),
+            ));
+        }
+        Ok(self.source[digits_start..self.current]
+            .iter()
+            .filter_map(|ch| ch.to_digit(radix))
+            .fold(0.0, |number, digit| number * radix as f64 + digit as f64))
+    }
+
+    /// Consumes a run of digits in `radix`, allowing single `_` separators
+    /// between them. Returns how many digits were consumed.
+    fn digits(&mut self, radix: u32) -> Result<usize, LoxResult> {
+        let mut count = 0;
+        loop {
+            match self.peek() {
+                Some(ch) if ch.is_digit(radix) => {
+                    self.advance();
+                    count += 1;
+                }
+                Some('_') => {
+                    let column = self.column(self.current);
+                    self.advance();
+                    if count == 0 || !self.peek().is_some_and(|ch| ch.is_digit(radix)) {
+                        return Err(LoxResult::scan_error(
+                            self.line,
+                            column,
+                            "Digit separator '_' must be between digits.",
+                        ));
+                    }
                 }
+                _ => return Ok(count),
             }
         }
-        let value: String = self.source[self.start..self.current].iter().collect();
-        let number: f64 = value.parse().unwrap();
-        self.add_token_object(TokenType::Number, Some(Object::Num(number)));
-        Ok(())
     }
 
     fn identifier(&mut self) -> Result<(), LoxResult>{
-        while Scanner::is_alphanumeric(self.peek()) {
+        while Scanner::is_identifier_continue(self.peek()) {
             self.advance();
         }
         let word: String = self.source[self.start..self.current].iter().collect();